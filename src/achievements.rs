@@ -0,0 +1,324 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::{
+    components::{
+        AchievementToast, AchievementsScreen, AchievementsText, PlayfieldRoot, TextFonts,
+    },
+    consts::{self, COMBO_MAX_MULTIPLIER, SIDE_MARGIN_PX},
+    entity::{Achievement, AppState, Career, Combo, GameLevel, GameState, PlayerState, RunStats},
+    events::{
+        AchievementUnlocked, EnemyKilled, GameOver, GameSet, LevelUp, PlayerHit, ScoreGained,
+    },
+    fonts::{FontRegistry, FontStyle},
+    locale::{fill, Locale, Strings},
+    stats::stats_event_system,
+    storage,
+};
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load::<Career>("career"))
+            // the run stats of the final frame are counted by then
+            .add_system(
+                career_system
+                    .in_set(GameSet::React)
+                    .after(stats_event_system),
+            )
+            .add_system(achievement_check_system.in_set(GameSet::React))
+            // after every system that may ask to quit this frame
+            .add_system(career_exit_system.in_base_set(CoreSet::Last))
+            .add_system(career_time_system.in_set(OnUpdate(AppState::InGame)))
+            .add_system(achievement_toast_spawn_system.in_set(GameSet::Present))
+            .add_system(achievement_toast_system)
+            .add_system(achievements_screen_toggle_system)
+            .add_system(achievements_screen_spawn.in_schedule(OnEnter(AppState::Achievements)))
+            .add_system(achievements_screen_despawn.in_schedule(OnExit(AppState::Achievements)))
+            .add_system(achievements_screen_text_system.in_set(OnUpdate(AppState::Achievements)));
+    }
+}
+
+fn achievement_name(strings: &Strings, achievement: Achievement) -> &'static str {
+    match achievement {
+        Achievement::FirstKill => strings.achievement_first_kill,
+        Achievement::ComboMaster => strings.achievement_combo_master,
+        Achievement::NoHitLevel => strings.achievement_no_hit_level,
+        Achievement::ReachInvincible => strings.achievement_reach_invincible,
+        Achievement::HundredKillRun => strings.achievement_hundred_kill_run,
+    }
+}
+
+/// Lifetime stats, saved whenever a run ends and when the game quits
+fn career_system(
+    mut career: ResMut<Career>,
+    run_stats: Res<RunStats>,
+    game_state: Res<GameState>,
+    mut killed_events: EventReader<EnemyKilled>,
+    mut game_over_events: EventReader<GameOver>,
+) {
+    let kills = killed_events.iter().count() as u32;
+    let game_overs = game_over_events.iter().count();
+    if game_state.cheated {
+        return;
+    }
+
+    career.total_kills += kills;
+    for _ in 0..game_overs {
+        career.runs_played += 1;
+        career.best_accuracy = career.best_accuracy.max(run_stats.accuracy());
+        storage::save("career", &*career);
+    }
+}
+
+/// Kills and play time of a run cut short by quitting
+fn career_exit_system(career: Res<Career>, mut exit_events: EventReader<AppExit>) {
+    if exit_events.iter().count() > 0 {
+        storage::save("career", &*career);
+    }
+}
+
+fn career_time_system(time: Res<Time>, game_state: Res<GameState>, mut career: ResMut<Career>) {
+    if !game_state.is_over && !game_state.cheated {
+        career.play_time += time.delta_seconds();
+    }
+}
+
+fn achievement_check_system(
+    mut career: ResMut<Career>,
+    game_state: Res<GameState>,
+    player_state: Res<PlayerState>,
+    combo: Res<Combo>,
+    mut hit_since_level_up: Local<bool>,
+    mut killed_events: EventReader<EnemyKilled>,
+    mut score_events: EventReader<ScoreGained>,
+    mut hit_events: EventReader<PlayerHit>,
+    mut level_up_events: EventReader<LevelUp>,
+    mut game_over_events: EventReader<GameOver>,
+    mut unlocked: EventWriter<AchievementUnlocked>,
+) {
+    let mut reached = Vec::new();
+
+    if killed_events.iter().count() > 0 {
+        reached.push(Achievement::FirstKill);
+    }
+    if player_state.total_score >= consts::ACHIEVEMENT_RUN_KILLS {
+        reached.push(Achievement::HundredKillRun);
+    }
+    if score_events.iter().count() > 0 && combo.multiplier() >= COMBO_MAX_MULTIPLIER {
+        reached.push(Achievement::ComboMaster);
+    }
+
+    // a level counts as cleared without a hit when the next one begins
+    if hit_events.iter().count() > 0 {
+        *hit_since_level_up = true;
+    }
+    for LevelUp(level) in level_up_events.iter() {
+        if !*hit_since_level_up {
+            reached.push(Achievement::NoHitLevel);
+        }
+        if *level == GameLevel::Invincible {
+            reached.push(Achievement::ReachInvincible);
+        }
+        *hit_since_level_up = false;
+    }
+    if game_over_events.iter().count() > 0 {
+        *hit_since_level_up = false;
+    }
+
+    if game_state.cheated {
+        return;
+    }
+
+    let mut changed = false;
+    for achievement in reached {
+        if career.unlock(achievement) {
+            unlocked.send(AchievementUnlocked(achievement));
+            changed = true;
+        }
+    }
+    if changed {
+        storage::save("career", &*career);
+    }
+}
+
+fn achievement_toast_spawn_system(
+    mut commands: Commands,
+    fonts: Res<FontRegistry>,
+    locale: Res<Locale>,
+    mut unlocked_events: EventReader<AchievementUnlocked>,
+    root_query: Query<Entity, With<PlayfieldRoot>>,
+) {
+    for AchievementUnlocked(achievement) in unlocked_events.iter() {
+        let strings = locale.strings();
+        commands
+            .spawn(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.), Val::Auto),
+                    position_type: PositionType::Absolute,
+                    justify_content: JustifyContent::Center,
+                    position: UiRect {
+                        bottom: Val::Px(SIDE_MARGIN_PX * 8.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                z_index: ZIndex::Global(15),
+                ..default()
+            })
+            .with_children(|builder| {
+                builder
+                    .spawn(NodeBundle {
+                        style: Style {
+                            padding: UiRect::all(Val::Px(SIDE_MARGIN_PX)),
+                            ..default()
+                        },
+                        background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.7)),
+                        ..default()
+                    })
+                    .with_children(|builder| {
+                        builder
+                            .spawn(TextBundle::from_sections([TextSection::new(
+                                fill(
+                                    strings.achievement_unlocked,
+                                    achievement_name(strings, *achievement),
+                                ),
+                                fonts.style(&locale, FontStyle::Hud, Color::GOLD),
+                            )]))
+                            .insert(TextFonts(vec![FontStyle::Hud]));
+                    });
+            })
+            .insert(AchievementToast(Timer::from_seconds(
+                consts::ACHIEVEMENT_TOAST_DURATION,
+                TimerMode::Once,
+            )))
+            .set_parent(root_query.single());
+    }
+}
+
+fn achievement_toast_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut AchievementToast)>,
+) {
+    for (entity, mut toast) in query.iter_mut() {
+        if toast.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn achievements_screen_toggle_system(
+    kb: Res<Input<KeyCode>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if kb.just_pressed(KeyCode::H) {
+        match state.0 {
+            AppState::InGame => next_state.set(AppState::Achievements),
+            AppState::Achievements => next_state.set(AppState::InGame),
+            _ => {}
+        }
+    }
+}
+
+fn achievements_screen_spawn(
+    mut commands: Commands,
+    fonts: Res<FontRegistry>,
+    locale: Res<Locale>,
+    root_query: Query<Entity, With<PlayfieldRoot>>,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            z_index: ZIndex::Global(20),
+            ..default()
+        })
+        .with_children(|builder| {
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(SIDE_MARGIN_PX * 2.)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.8)),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder
+                        .spawn(TextBundle::from_sections([TextSection::new(
+                            locale.strings().achievements_title,
+                            fonts.style(&locale, FontStyle::Title, Color::WHITE),
+                        )]))
+                        .insert(TextFonts(vec![FontStyle::Title]));
+                    builder
+                        .spawn(TextBundle::from_sections([TextSection::new(
+                            "",
+                            fonts.style(&locale, FontStyle::Hud, Color::GOLD),
+                        )]))
+                        .insert(TextFonts(vec![FontStyle::Hud]))
+                        .insert(AchievementsText);
+                });
+        })
+        .insert(AchievementsScreen)
+        .set_parent(root_query.single());
+}
+
+fn achievements_screen_despawn(
+    mut commands: Commands,
+    query: Query<Entity, With<AchievementsScreen>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn achievements_screen_text_system(
+    career: Res<Career>,
+    locale: Res<Locale>,
+    mut query: Query<&mut Text, With<AchievementsText>>,
+) {
+    let strings = locale.strings();
+    let minutes = career.play_time as u32 / 60;
+    let mut lines = vec![
+        fill(strings.career_kills, career.total_kills),
+        fill(strings.career_runs, career.runs_played),
+        fill(
+            strings.career_time,
+            format!("{}:{:02}", minutes / 60, minutes % 60),
+        ),
+        fill(
+            strings.career_accuracy,
+            format!("{:.1}%", career.best_accuracy),
+        ),
+        String::new(),
+    ];
+    for achievement in Achievement::ALL {
+        let mark = if career.achievements.contains(&achievement) {
+            "[x]"
+        } else {
+            "[ ]"
+        };
+        lines.push(format!(
+            "{} {}",
+            mark,
+            achievement_name(strings, achievement)
+        ));
+    }
+    lines.push(format!("\n{}", strings.achievements_back));
+    let txt = lines.join("\n");
+
+    for mut text in &mut query {
+        if text.sections[0].value != txt {
+            text.sections[0].value = txt.clone();
+        }
+    }
+}
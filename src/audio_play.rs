@@ -1,47 +1,67 @@
 use bevy::prelude::*;
 
 use crate::{
-    consts,
-    entity::{GameLevel, Settings},
+    entity::{GameAudio, GameLevel, Settings},
+    events::{GameOver, GameSet, LaserFired, LevelUp},
 };
 
-pub fn leve_upgrade(settings: &Res<Settings>, asset_server: &Res<AssetServer>, audio: &Res<Audio>) {
-    if !settings.mute {
-        audio.play(asset_server.load(consts::AUDIOS_LEVEL_UPGRADE));
+pub struct AudioPlayPlugin;
+
+impl Plugin for AudioPlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlaySfx>()
+            .add_system(game_events_sfx_system.in_set(GameSet::Present))
+            .add_system(play_sfx_system.after(game_events_sfx_system));
     }
 }
 
-pub fn explosion(
-    index: usize,
-    settings: &Res<Settings>,
-    asset_server: &Res<AssetServer>,
-    audio: &Res<Audio>,
-) {
-    if !settings.mute && index == 0 {
-        audio.play(asset_server.load(consts::AUDIOS_EXPLOSION));
-    }
+/// Event - a one-shot sound effect requested by gameplay
+pub enum PlaySfx {
+    LevelUpgrade,
+    Explosion,
+    GameOver,
+    Shot(GameLevel),
 }
 
-pub fn game_over(settings: &Res<Settings>, asset_server: &Res<AssetServer>, audio: &Res<Audio>) {
-    if !settings.mute {
-        audio.play(asset_server.load(consts::AUDIOS_PLAYER_FAIL));
+fn game_events_sfx_system(
+    mut level_up_events: EventReader<LevelUp>,
+    mut game_over_events: EventReader<GameOver>,
+    mut fired_events: EventReader<LaserFired>,
+    mut sfx: EventWriter<PlaySfx>,
+) {
+    for _ in level_up_events.iter() {
+        sfx.send(PlaySfx::LevelUpgrade);
+    }
+    for _ in game_over_events.iter() {
+        sfx.send(PlaySfx::GameOver);
+    }
+    for fired in fired_events.iter() {
+        sfx.send(PlaySfx::Shot(fired.level));
     }
 }
 
-pub fn fire_shot(
-    game_leve: GameLevel,
-    settings: &Res<Settings>,
-    asset_server: &Res<AssetServer>,
-    audio: &Res<Audio>,
+fn play_sfx_system(
+    settings: Res<Settings>,
+    audio: Res<Audio>,
+    game_audio: Res<GameAudio>,
+    mut events: EventReader<PlaySfx>,
 ) {
-    if !settings.mute {
-        let music = match game_leve {
-            GameLevel::Basic | GameLevel::Middle => asset_server.load(consts::AUDIOS_SHOT_LOW),
-            GameLevel::Strong => asset_server.load(consts::AUDIOS_SHOT_MID),
-            GameLevel::Powerful | GameLevel::Invincible => {
-                asset_server.load(consts::AUDIOS_SHOT_HIGH)
-            }
+    if settings.mute {
+        events.clear();
+        return;
+    }
+    for sfx in events.iter() {
+        let source = match sfx {
+            PlaySfx::LevelUpgrade => &game_audio.level_upgrade,
+            PlaySfx::Explosion => &game_audio.explosion,
+            PlaySfx::GameOver => &game_audio.player_fail,
+            PlaySfx::Shot(GameLevel::Basic | GameLevel::Middle) => &game_audio.shot_low,
+            PlaySfx::Shot(GameLevel::Strong) => &game_audio.shot_mid,
+            PlaySfx::Shot(GameLevel::Powerful | GameLevel::Invincible) => &game_audio.shot_high,
         };
-        audio.play(music);
+        audio.play_with_settings(
+            source.clone(),
+            PlaybackSettings::ONCE.with_volume(settings.volume),
+        );
     }
 }
@@ -0,0 +1,205 @@
+use std::time::Instant;
+
+use bevy::prelude::*;
+use rand::{thread_rng, Rng};
+
+use crate::consts::{BROADPHASE_CELL, BROADPHASE_MARGIN, PLAYFIELD_SIZE};
+
+/// Resource - uniform grid over the playfield (and the margin sprites fly in from),
+/// rebuilt every frame from the colliders something can hit.
+/// Sprites outside the grid are kept in the border cells.
+#[derive(Resource)]
+pub struct Broadphase {
+    cell: f32,
+    origin: Vec2,
+    cols: usize,
+    rows: usize,
+    cells: Vec<Vec<usize>>,
+    items: Vec<(Entity, Rect)>,
+}
+
+impl Broadphase {
+    pub fn new(cell: f32) -> Self {
+        let size = Vec2::new(PLAYFIELD_SIZE.0, PLAYFIELD_SIZE.1) + BROADPHASE_MARGIN * 2.;
+        let cols = (size.x / cell).ceil() as usize;
+        let rows = (size.y / cell).ceil() as usize;
+        Self {
+            cell,
+            origin: -size / 2.,
+            cols,
+            rows,
+            cells: vec![Vec::new(); cols * rows],
+            items: Vec::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for cell in self.cells.iter_mut() {
+            cell.clear();
+        }
+        self.items.clear();
+    }
+
+    pub fn insert(&mut self, entity: Entity, rect: Rect) {
+        let idx = self.items.len();
+        self.items.push((entity, rect));
+        let ((x0, y0), (x1, y1)) = self.cell_range(rect);
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                self.cells[y * self.cols + x].push(idx);
+            }
+        }
+    }
+
+    /// Entities whose rect overlaps `rect`, each listed once
+    pub fn query(&self, rect: Rect) -> Vec<Entity> {
+        let mut found = Vec::new();
+        let ((x0, y0), (x1, y1)) = self.cell_range(rect);
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                for &idx in &self.cells[y * self.cols + x] {
+                    if !self.items[idx].1.intersect(rect).is_empty() {
+                        found.push(idx);
+                    }
+                }
+            }
+        }
+        found.sort_unstable();
+        found.dedup();
+        found.into_iter().map(|idx| self.items[idx].0).collect()
+    }
+
+    fn cell_range(&self, rect: Rect) -> ((usize, usize), (usize, usize)) {
+        let to_cell = |point: Vec2| {
+            let cell = ((point - self.origin) / self.cell).floor();
+            (
+                (cell.x.max(0.) as usize).min(self.cols - 1),
+                (cell.y.max(0.) as usize).min(self.rows - 1),
+            )
+        };
+        (to_cell(rect.min), to_cell(rect.max))
+    }
+}
+
+/// `--bench-collisions`: time the grid against the nested loop it replaced,
+/// with thousands of lasers over a screen of enemies
+pub fn bench() {
+    const FRAMES: u32 = 300;
+    const FRAME_BUDGET_MS: f64 = 1000. / 60.;
+
+    let mut rng = thread_rng();
+    let half = Vec2::new(PLAYFIELD_SIZE.0, PLAYFIELD_SIZE.1) / 2.;
+    let mut random_rect = |size: Vec2| {
+        let center = Vec2::new(
+            rng.gen_range(-half.x..half.x),
+            rng.gen_range(-half.y..half.y),
+        );
+        Rect::from_center_size(center, size)
+    };
+    let enemies: Vec<Rect> = (0..60).map(|_| random_rect(Vec2::new(72., 37.5))).collect();
+
+    println!("lasers    grid ms/frame    nested loop ms/frame");
+    for lasers in [500, 2000, 5000, 10000] {
+        let lasers: Vec<Rect> = (0..lasers)
+            .map(|_| random_rect(Vec2::new(4.5, 27.)))
+            .collect();
+
+        let mut broadphase = Broadphase::new(BROADPHASE_CELL);
+        let mut grid_hits = 0;
+        let start = Instant::now();
+        for _ in 0..FRAMES {
+            broadphase.clear();
+            for (i, rect) in enemies.iter().enumerate() {
+                broadphase.insert(Entity::from_raw(i as u32), *rect);
+            }
+            for laser in &lasers {
+                grid_hits += broadphase.query(*laser).len();
+            }
+        }
+        let grid_ms = start.elapsed().as_secs_f64() * 1000. / FRAMES as f64;
+
+        let mut nested_hits = 0;
+        let start = Instant::now();
+        for _ in 0..FRAMES {
+            for laser in &lasers {
+                nested_hits += enemies
+                    .iter()
+                    .filter(|enemy| !enemy.intersect(*laser).is_empty())
+                    .count();
+            }
+        }
+        let nested_ms = start.elapsed().as_secs_f64() * 1000. / FRAMES as f64;
+
+        assert_eq!(grid_hits, nested_hits, "the grid must find every overlap");
+        println!(
+            "{:>6}    {:>13.3}    {:>20.3}    ({})",
+            lasers.len(),
+            grid_ms,
+            nested_ms,
+            if grid_ms < FRAME_BUDGET_MS {
+                "fits a 60 FPS frame"
+            } else {
+                "over a 60 FPS frame"
+            }
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_finds_each_overlapping_entity_once() {
+        let mut grid = Broadphase::new(BROADPHASE_CELL);
+        let (big, small, far) = (
+            Entity::from_raw(1),
+            Entity::from_raw(2),
+            Entity::from_raw(3),
+        );
+        // spans many cells
+        grid.insert(big, Rect::new(-200., -200., 200., 200.));
+        grid.insert(
+            small,
+            Rect::from_center_size(Vec2::new(10., 10.), Vec2::splat(4.)),
+        );
+        grid.insert(
+            far,
+            Rect::from_center_size(Vec2::new(250., 250.), Vec2::splat(4.)),
+        );
+
+        let mut found = grid.query(Rect::new(-150., -150., 150., 150.));
+        found.sort();
+        assert_eq!(found, [big, small]);
+    }
+
+    #[test]
+    fn query_skips_touching_rects() {
+        let mut grid = Broadphase::new(BROADPHASE_CELL);
+        let entity = Entity::from_raw(1);
+        grid.insert(entity, Rect::new(0., 0., 10., 10.));
+        assert!(grid.query(Rect::new(10., 0., 20., 10.)).is_empty());
+        assert_eq!(grid.query(Rect::new(9., 0., 20., 10.)), [entity]);
+    }
+
+    #[test]
+    fn rects_outside_the_grid_are_kept_in_the_border_cells() {
+        let mut grid = Broadphase::new(BROADPHASE_CELL);
+        let (outside, border) = (Entity::from_raw(1), Entity::from_raw(2));
+        let edge = PLAYFIELD_SIZE.0 / 2. + BROADPHASE_MARGIN;
+        grid.insert(outside, Rect::new(edge + 1000., 0., edge + 1010., 10.));
+        grid.insert(border, Rect::new(edge - 10., 0., edge - 1., 10.));
+
+        // the far rect is found by a query out there, the border cell still checks the rects
+        assert_eq!(
+            grid.query(Rect::new(edge + 1005., 0., edge + 2000., 5.)),
+            [outside]
+        );
+        assert_eq!(grid.query(Rect::new(edge - 5., 0., edge, 5.)), [border]);
+
+        grid.clear();
+        assert!(grid
+            .query(Rect::new(edge + 1005., 0., edge + 2000., 5.))
+            .is_empty());
+    }
+}
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+use crate::{
+    broadphase::Broadphase,
+    components::Collider,
+    consts::BROADPHASE_CELL,
+    entity::AppState,
+    events::{CollisionEvent, GameSet},
+};
+
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Broadphase::new(BROADPHASE_CELL))
+            .add_systems(
+                (broadphase_rebuild_system, collision_detect_system)
+                    .chain()
+                    .in_set(GameSet::Collide)
+                    .in_set(OnUpdate(AppState::InGame)),
+            );
+    }
+}
+
+/// Only colliders on a layer some mask looks for are indexed,
+/// so the lasers nothing can hit don't crowd the cells
+fn broadphase_rebuild_system(
+    mut broadphase: ResMut<Broadphase>,
+    query: Query<(Entity, &Transform, &Collider)>,
+) {
+    let targets = query
+        .iter()
+        .fold(0, |targets, (_, _, collider)| targets | collider.mask);
+
+    broadphase.clear();
+    for (entity, transform, collider) in query.iter() {
+        if collider.layer & targets != 0 {
+            broadphase.insert(entity, collider.bounds(transform));
+        }
+    }
+}
+
+fn collision_detect_system(
+    broadphase: Res<Broadphase>,
+    query: Query<(Entity, &Transform, &Collider)>,
+    mut collisions: EventWriter<CollisionEvent>,
+) {
+    for (entity, transform, collider) in query.iter() {
+        if collider.mask == 0 {
+            continue;
+        }
+
+        for other in broadphase.query(collider.bounds(transform)) {
+            if other == entity {
+                continue;
+            }
+            if let Ok((_, other_tf, other_collider)) = query.get(other) {
+                if collider.mask & other_collider.layer != 0
+                    && collider.overlaps(transform, other_collider, other_tf)
+                {
+                    collisions.send(CollisionEvent(entity, other));
+                }
+            }
+        }
+    }
+}
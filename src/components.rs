@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::text::{get_current_score_text, get_history_text, get_lives_text, get_total_score_text};
+use crate::{consts, fonts::FontStyle, locale::Strings};
 
 // region: --- Common Components
 
@@ -15,6 +15,12 @@ pub struct Movable {
     pub auto_despawn: bool,
 }
 
+/// Keep the whole sprite inside `area`, or the [`PlayArea`](crate::entity::PlayArea) when `None`
+#[derive(Component, Default)]
+pub struct Bounded {
+    pub area: Option<Rect>,
+}
+
 #[derive(Component)]
 pub struct Laser;
 
@@ -27,52 +33,189 @@ impl From<(f32, f32)> for SpriteSize {
     }
 }
 
+#[derive(Component)]
+pub struct ScoreText;
+
 #[derive(Component)]
 pub struct TotalScoreText;
 
-impl TotalScoreText {
-    pub fn update(mut query: Query<&mut Text, With<TotalScoreText>>, score: u32) {
-        for mut text in &mut query {
-            text.sections[0].value = get_total_score_text(score);
-        }
-    }
+#[derive(Component)]
+pub struct CurrentScoreText;
+
+#[derive(Component)]
+pub struct HistoryScoreText;
+
+#[derive(Component)]
+pub struct LifeText;
+
+#[derive(Component)]
+pub struct GameOverText;
+
+/// Stats of the finished run on the game over panel
+#[derive(Component)]
+pub struct RunSummaryText;
+
+/// Celebrates a run that entered the leaderboard, despawned with the game over panel
+#[derive(Component)]
+pub struct HighScoreText {
+    pub rank: usize,
 }
 
+/// Ui node covering the playfield, parent of the HUD
 #[derive(Component)]
-pub struct CurrentScoreText;
-impl CurrentScoreText {
-    pub fn update(mut query: Query<&mut Text, With<CurrentScoreText>>, score: u32) {
-        for mut text in &mut query {
-            text.sections[0].value = get_current_score_text(score);
+pub struct PlayfieldRoot;
+
+/// Static text looked up in the string table of the active language
+#[derive(Component)]
+pub struct LocalizedText(pub fn(&Strings) -> &'static str);
+
+/// The `FontStyle` of every section, the fonts are resolved again when the language changes
+#[derive(Component)]
+pub struct TextFonts(pub Vec<FontStyle>);
+
+// endregion: --- Common Components
+
+// region: --- Pool Components
+
+#[derive(Clone, Copy)]
+pub enum PoolKind {
+    PlayerLaser,
+    EnemyLaser,
+    Explosion,
+}
+
+impl PoolKind {
+    pub const COUNT: usize = 3;
+}
+
+/// Entity recycled through [`Pools`](crate::pool::Pools), parked hidden while not `active`
+#[derive(Component)]
+pub struct Pooled {
+    pub kind: PoolKind,
+    pub active: bool,
+}
+
+impl Pooled {
+    pub fn new(kind: PoolKind) -> Self {
+        Self { kind, active: true }
+    }
+}
+
+// endregion: --- Pool Components
+
+// region: --- Collision Components
+
+/// Shape in sprite pixels, scaled by the `Transform` like the sprite
+#[derive(Clone, Copy)]
+pub enum ColliderShape {
+    Aabb(Vec2),
+    Circle(f32),
+}
+
+/// A shape placed at `offset` from the sprite center, in sprite pixels with y up
+#[derive(Clone, Copy)]
+pub struct Hitbox {
+    pub offset: Vec2,
+    pub shape: ColliderShape,
+}
+
+impl Hitbox {
+    pub const fn aabb(offset: (f32, f32), size: (f32, f32)) -> Self {
+        Self {
+            offset: Vec2::new(offset.0, offset.1),
+            shape: ColliderShape::Aabb(Vec2::new(size.0, size.1)),
+        }
+    }
+
+    pub const fn circle(offset: (f32, f32), radius: f32) -> Self {
+        Self {
+            offset: Vec2::new(offset.0, offset.1),
+            shape: ColliderShape::Circle(radius),
         }
     }
+
+    /// Center and shape in world space, the offset follows the sprite's rotation and scale
+    fn world(&self, transform: &Transform) -> (Vec2, ColliderShape) {
+        let scale = transform.scale.truncate().abs();
+        let offset = transform.rotation * (self.offset * scale).extend(0.);
+        let center = transform.translation.truncate() + offset.truncate();
+        let shape = match self.shape {
+            ColliderShape::Aabb(size) => ColliderShape::Aabb(size * scale),
+            ColliderShape::Circle(radius) => ColliderShape::Circle(radius * scale.max_element()),
+        };
+        (center, shape)
+    }
 }
 
+/// Collides as `layer` and reports every collider whose layer is in `mask`,
+/// touching any of its hitboxes counts
 #[derive(Component)]
-pub struct HistoryScoreText;
-impl HistoryScoreText {
-    pub fn update(mut query: Query<&mut Text, With<HistoryScoreText>>, score: u32) {
-        for mut text in &mut query {
-            text.sections[0].value = get_history_text(score);
+pub struct Collider {
+    pub hitboxes: &'static [Hitbox],
+    pub layer: u32,
+    pub mask: u32,
+}
+
+impl Collider {
+    pub fn new(hitboxes: &'static [Hitbox], layer: u32, mask: u32) -> Self {
+        Self {
+            hitboxes,
+            layer,
+            mask,
         }
     }
+
+    /// World space bounds of all the hitboxes, what the broadphase indexes
+    pub fn bounds(&self, transform: &Transform) -> Rect {
+        self.hitboxes
+            .iter()
+            .map(|hitbox| shape_bounds(hitbox.world(transform)))
+            .reduce(|a, b| a.union(b))
+            .unwrap_or_default()
+    }
+
+    pub fn overlaps(&self, transform: &Transform, other: &Collider, other_tf: &Transform) -> bool {
+        self.hitboxes.iter().any(|hitbox| {
+            let a = hitbox.world(transform);
+            other
+                .hitboxes
+                .iter()
+                .any(|other_hitbox| shapes_overlap(a, other_hitbox.world(other_tf)))
+        })
+    }
 }
 
-#[derive(Component)]
-pub struct LifeText;
+fn shape_bounds((center, shape): (Vec2, ColliderShape)) -> Rect {
+    match shape {
+        ColliderShape::Aabb(size) => Rect::from_center_size(center, size),
+        ColliderShape::Circle(radius) => Rect::from_center_size(center, Vec2::splat(radius * 2.)),
+    }
+}
 
-impl LifeText {
-    pub fn update(mut query: Query<&mut Text, With<LifeText>>, lives: u32) {
-        for mut text in &mut query {
-            text.sections[0].value = get_lives_text(lives);
+fn shapes_overlap(a: (Vec2, ColliderShape), b: (Vec2, ColliderShape)) -> bool {
+    match (a.1, b.1) {
+        (ColliderShape::Aabb(_), ColliderShape::Aabb(_)) => {
+            !shape_bounds(a).intersect(shape_bounds(b)).is_empty()
+        }
+        (ColliderShape::Circle(ra), ColliderShape::Circle(rb)) => {
+            a.0.distance_squared(b.0) < (ra + rb) * (ra + rb)
+        }
+        (ColliderShape::Aabb(size), ColliderShape::Circle(radius)) => {
+            circle_overlaps_aabb(b.0, radius, a.0, size)
+        }
+        (ColliderShape::Circle(radius), ColliderShape::Aabb(size)) => {
+            circle_overlaps_aabb(a.0, radius, b.0, size)
         }
     }
 }
 
-#[derive(Component)]
-pub struct GameOverText;
+fn circle_overlaps_aabb(center: Vec2, radius: f32, box_center: Vec2, box_size: Vec2) -> bool {
+    let half = box_size / 2.;
+    let closest = center.clamp(box_center - half, box_center + half);
+    center.distance_squared(closest) < radius * radius
+}
 
-// endregion: --- Common Components
+// endregion: --- Collision Components
 
 // region: --- Player Components
 #[derive(Component)]
@@ -88,6 +231,27 @@ pub struct Enemy;
 
 #[derive(Component)]
 pub struct FromEnemy;
+
+/// Whether player lasers can shoot this enemy laser down when interception is on,
+/// data rather than a marker so a reused laser keeps its archetype
+#[derive(Component)]
+pub struct Interceptible(pub bool);
+
+/// Elites are tinted and worth more points
+#[derive(Component, Clone, Copy)]
+pub enum EnemyKind {
+    Grunt,
+    Elite,
+}
+
+impl EnemyKind {
+    pub fn points(&self) -> u32 {
+        match self {
+            EnemyKind::Grunt => consts::ENEMY_GRUNT_POINTS,
+            EnemyKind::Elite => consts::ENEMY_ELITE_POINTS,
+        }
+    }
+}
 // endregion: --- Enemy Components
 
 // region: --- Explosion Components
@@ -100,6 +264,14 @@ pub struct ExplosionToSpawn(pub Vec3);
 #[derive(Component)]
 pub struct ExplosionTimer(pub Timer);
 
+/// Flash left where two lasers met, shrinking and fading out
+#[derive(Component)]
+pub struct Spark(pub Timer);
+
+/// Points floating up from a kill site and fading out
+#[derive(Component)]
+pub struct ScorePopup(pub Timer);
+
 impl Default for ExplosionTimer {
     fn default() -> Self {
         Self(Timer::from_seconds(0.05, bevy::time::TimerMode::Repeating))
@@ -107,7 +279,168 @@ impl Default for ExplosionTimer {
 }
 // endregion: --- Explosion Components
 
+// region: --- Loading Components
+#[derive(Component)]
+pub struct LoadingScreen;
+
+#[derive(Component)]
+pub struct LoadingProgressText;
+
+#[derive(Component)]
+pub struct LoadingErrorText;
+// endregion: --- Loading Components
+
 // region: --- Settings Components
 #[derive(Component)]
 pub struct AudioButton;
+
+#[derive(Component)]
+pub struct SettingsScreen;
+
+#[derive(Component)]
+pub struct SettingsText;
 // endregion: --- Settings Components
+
+// region: --- Achievements Components
+/// Pops up for a while when an achievement unlocks
+#[derive(Component)]
+pub struct AchievementToast(pub Timer);
+
+#[derive(Component)]
+pub struct AchievementsScreen;
+
+#[derive(Component)]
+pub struct AchievementsText;
+// endregion: --- Achievements Components
+
+// region: --- Debug Components
+#[derive(Component)]
+pub struct DebugText;
+
+/// Outline of one hitbox, child of the collider it shows
+#[derive(Component)]
+pub struct HitboxGizmo;
+
+/// The collider's hitboxes are drawn
+#[derive(Component)]
+pub struct HitboxDrawn;
+
+/// Dot of the ellipse flown by the formation with this id
+#[derive(Component)]
+pub struct FormationPath(pub u32);
+// endregion: --- Debug Components
+
+// region: --- Name Entry Components
+#[derive(Component)]
+pub struct NameEntryScreen;
+
+#[derive(Component)]
+pub struct NameEntryText;
+// endregion: --- Name Entry Components
+
+// region: --- Console Components
+#[derive(Component)]
+pub struct ConsoleScreen;
+
+#[derive(Component)]
+pub struct ConsoleText;
+// endregion: --- Console Components
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+
+    fn aabb(x: f32, y: f32, w: f32, h: f32) -> (Vec2, ColliderShape) {
+        (Vec2::new(x, y), ColliderShape::Aabb(Vec2::new(w, h)))
+    }
+
+    fn circle(x: f32, y: f32, radius: f32) -> (Vec2, ColliderShape) {
+        (Vec2::new(x, y), ColliderShape::Circle(radius))
+    }
+
+    #[test]
+    fn touching_edges_do_not_overlap() {
+        assert!(!shapes_overlap(
+            aabb(0., 0., 10., 10.),
+            aabb(10., 0., 10., 10.)
+        ));
+        assert!(shapes_overlap(
+            aabb(0., 0., 10., 10.),
+            aabb(9.9, 0., 10., 10.)
+        ));
+
+        assert!(!shapes_overlap(circle(0., 0., 5.), circle(10., 0., 5.)));
+        assert!(shapes_overlap(circle(0., 0., 5.), circle(9.9, 0., 5.)));
+
+        assert!(!shapes_overlap(circle(0., 0., 5.), aabb(10., 0., 10., 10.)));
+        assert!(shapes_overlap(aabb(10., 0., 10., 10.), circle(0.1, 0., 5.)));
+    }
+
+    #[test]
+    fn circle_misses_the_corner_of_its_bounds() {
+        // inside the circle's bounding square, outside the circle
+        assert!(!circle_overlaps_aabb(
+            Vec2::ZERO,
+            5.,
+            Vec2::new(5.5, 5.5),
+            Vec2::splat(3.)
+        ));
+        assert!(circle_overlaps_aabb(
+            Vec2::ZERO,
+            5.,
+            Vec2::new(4., 4.),
+            Vec2::splat(3.)
+        ));
+        // the center inside the box
+        assert!(circle_overlaps_aabb(
+            Vec2::ZERO,
+            1.,
+            Vec2::ZERO,
+            Vec2::splat(100.)
+        ));
+    }
+
+    #[test]
+    fn hitbox_follows_scale_and_flip() {
+        // the enemies are flipped upside down with `from_rotation_x(PI)`
+        let transform = Transform {
+            translation: Vec3::new(100., 50., 0.),
+            rotation: Quat::from_rotation_x(PI),
+            scale: Vec3::new(0.5, 0.5, 1.),
+        };
+        let (center, shape) = Hitbox::circle((0., 12.), 36.).world(&transform);
+        assert!(center.abs_diff_eq(Vec2::new(100., 44.), 1e-4));
+        assert!(matches!(shape, ColliderShape::Circle(radius) if radius == 18.));
+
+        let (center, shape) = Hitbox::aabb((-20., -26.), (24., 28.)).world(&transform);
+        assert!(center.abs_diff_eq(Vec2::new(90., 63.), 1e-4));
+        assert!(matches!(shape, ColliderShape::Aabb(size) if size == Vec2::new(12., 14.)));
+    }
+
+    #[test]
+    fn hitbox_offset_follows_rotation() {
+        let transform = Transform::from_rotation(Quat::from_rotation_z(FRAC_PI_2));
+        let (center, _) = Hitbox::aabb((10., 0.), (4., 4.)).world(&transform);
+        assert!(center.abs_diff_eq(Vec2::new(0., 10.), 1e-4));
+    }
+
+    #[test]
+    fn collider_overlaps_any_hitbox() {
+        const TWO_BOXES: &[Hitbox] = &[
+            Hitbox::aabb((-20., 0.), (10., 10.)),
+            Hitbox::aabb((20., 0.), (10., 10.)),
+        ];
+        const DOT: &[Hitbox] = &[Hitbox::circle((0., 0.), 1.)];
+        let collider = Collider::new(TWO_BOXES, 1, 2);
+        let dot = Collider::new(DOT, 2, 1);
+        let at = |x: f32| Transform::from_xyz(x, 0., 0.);
+
+        assert!(collider.overlaps(&at(0.), &dot, &at(20.)));
+        assert!(collider.overlaps(&at(0.), &dot, &at(-20.)));
+        // between the boxes, inside the bounds
+        assert!(!collider.overlaps(&at(0.), &dot, &at(0.)));
+        assert_eq!(collider.bounds(&at(0.)), Rect::new(-25., -5., 25., 5.));
+    }
+}
@@ -0,0 +1,238 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{ConsoleScreen, ConsoleText, PlayfieldRoot, TextFonts},
+    consts::{CONSOLE_LINES, SIDE_MARGIN_PX},
+    enemy::{self, FormationMaker},
+    entity::{AppState, EnemyState, GameLevel, GameState, GameTextures, PlayerState, WinSize},
+    fonts::{FontRegistry, FontStyle},
+    locale::Locale,
+    utils,
+};
+
+const HELP: &str =
+    "god | lives N | level NAME | spawn enemy N | score N | seed N | timescale X | help";
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Console::default())
+            .add_system(console_toggle_system)
+            .add_system(console_spawn_system.in_schedule(OnEnter(AppState::Console)))
+            .add_system(console_despawn_system.in_schedule(OnExit(AppState::Console)))
+            .add_systems(
+                (console_input_system, console_text_system)
+                    .chain()
+                    .in_set(OnUpdate(AppState::Console)),
+            );
+    }
+}
+
+/// Resource - the dev console, the line being typed and the latest output, kept across openings
+#[derive(Resource, Default)]
+struct Console {
+    input: String,
+    lines: Vec<String>,
+}
+
+impl Console {
+    fn print(&mut self, line: String) {
+        self.lines.push(line);
+        if self.lines.len() > CONSOLE_LINES {
+            self.lines.remove(0);
+        }
+    }
+}
+
+fn console_toggle_system(
+    kb: Res<Input<KeyCode>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if kb.just_pressed(KeyCode::Grave) {
+        match state.0 {
+            AppState::InGame => next_state.set(AppState::Console),
+            AppState::Console => next_state.set(AppState::InGame),
+            _ => {}
+        }
+    }
+}
+
+fn console_spawn_system(
+    mut commands: Commands,
+    fonts: Res<FontRegistry>,
+    locale: Res<Locale>,
+    root_query: Query<Entity, With<PlayfieldRoot>>,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Auto),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(0.),
+                    left: Val::Px(0.),
+                    ..default()
+                },
+                padding: UiRect::all(Val::Px(SIDE_MARGIN_PX)),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.8)),
+            z_index: ZIndex::Global(20),
+            ..default()
+        })
+        .with_children(|builder| {
+            builder
+                .spawn(TextBundle::from_sections([
+                    TextSection::new("", fonts.style(&locale, FontStyle::Hud, Color::GRAY)),
+                    TextSection::new("", fonts.style(&locale, FontStyle::Hud, Color::LIME_GREEN)),
+                ]))
+                .insert(TextFonts(vec![FontStyle::Hud, FontStyle::Hud]))
+                .insert(ConsoleText);
+        })
+        .insert(ConsoleScreen)
+        .set_parent(root_query.single());
+}
+
+fn console_despawn_system(mut commands: Commands, query: Query<Entity, With<ConsoleScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Typing edits the line, Enter runs it, Esc closes the console.
+/// Any command that changes the game marks the run as cheated
+fn console_input_system(
+    mut commands: Commands,
+    kb: Res<Input<KeyCode>>,
+    mut characters: EventReader<ReceivedCharacter>,
+    mut console: ResMut<Console>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut time: ResMut<Time>,
+    mut game_state: ResMut<GameState>,
+    mut player_state: ResMut<PlayerState>,
+    mut enemy_state: ResMut<EnemyState>,
+    mut formation_maker: ResMut<FormationMaker>,
+    game_textures: Res<GameTextures>,
+    win_size: Res<WinSize>,
+) {
+    for character in characters.iter() {
+        // the backtick toggles the console, control chars come with Back and Enter
+        let c = character.char;
+        if !c.is_control() && c != '`' && c != '~' {
+            console.input.push(c);
+        }
+    }
+    if kb.just_pressed(KeyCode::Back) {
+        console.input.pop();
+    }
+    if kb.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::InGame);
+        return;
+    }
+    if !kb.any_just_pressed([KeyCode::Return, KeyCode::NumpadEnter]) {
+        return;
+    }
+
+    let line = std::mem::take(&mut console.input);
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if words.is_empty() {
+        return;
+    }
+    console.print(format!("> {}", line.trim()));
+
+    let output = match words[..] {
+        ["god"] => {
+            game_state.god = !game_state.god;
+            game_state.cheated = true;
+            format!("god {}", if game_state.god { "on" } else { "off" })
+        }
+        ["lives", n] => match n.parse::<u32>() {
+            Ok(lives) if lives > 0 => {
+                player_state.lives = lives;
+                game_state.cheated = true;
+                format!("lives {}", lives)
+            }
+            _ => format!("lives: {} is not a count above 0", n),
+        },
+        ["level", name] => match GameLevel::from_name(name) {
+            Some(level) => {
+                // the kills of the level, so the next kill doesn't drop it back
+                player_state.total_score = level.min_kills();
+                player_state.current_score = level.min_kills();
+                enemy_state.update(level);
+                game_state.cheated = true;
+                format!("level {:?}", level)
+            }
+            None => format!("level: one of {:?}", GameLevel::ALL),
+        },
+        ["spawn", "enemy", n] => match n.parse::<u32>() {
+            Ok(count) => {
+                for _ in 0..count {
+                    enemy::spawn_enemy(
+                        &mut commands,
+                        &game_textures,
+                        &mut enemy_state,
+                        &mut formation_maker,
+                        &win_size,
+                    );
+                }
+                game_state.cheated = true;
+                format!("spawned {} enemies", count)
+            }
+            Err(_) => format!("spawn enemy: {} is not a count", n),
+        },
+        ["score", n] => match n.parse::<u32>() {
+            Ok(points) => {
+                player_state.points = points;
+                game_state.cheated = true;
+                format!("score {}", points)
+            }
+            Err(_) => format!("score: {} is not a number of points", n),
+        },
+        ["seed", n] => match n.parse::<u64>() {
+            Ok(seed) => {
+                utils::seed(seed);
+                game_state.cheated = true;
+                format!("seed {}", seed)
+            }
+            Err(_) => format!("seed: {} is not a number", n),
+        },
+        ["timescale", x] => match x.parse::<f32>() {
+            Ok(speed) if speed > 0. && speed.is_finite() => {
+                time.set_relative_speed(speed);
+                game_state.cheated = true;
+                format!("timescale {}", speed)
+            }
+            _ => format!("timescale: {} is not a speed above 0", x),
+        },
+        ["help"] => HELP.to_string(),
+        _ => format!("unknown command, try: {}", HELP),
+    };
+    console.print(output);
+}
+
+/// The output above the line being typed, the cursor blinks
+fn console_text_system(
+    time: Res<Time>,
+    console: Res<Console>,
+    mut query: Query<&mut Text, With<ConsoleText>>,
+) {
+    // real time, the console may have slowed the game clock down
+    let blink = (time.raw_elapsed_seconds() * 4.).sin() > 0.;
+    let output: String = console
+        .lines
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let input = format!("> {}{}", console.input, if blink { "_" } else { " " });
+    for mut text in &mut query {
+        if text.sections[0].value != output {
+            text.sections[0].value = output.clone();
+        }
+        if text.sections[1].value != input {
+            text.sections[1].value = input.clone();
+        }
+    }
+}
@@ -1,39 +1,102 @@
 use std::time::Duration;
 
+use bevy::prelude::Color;
+
+use crate::components::Hitbox;
+
 // region: --- OTHER
+pub(crate) const PLAYFIELD_SIZE: (f32, f32) = (598., 676.);
+pub(crate) const LETTERBOX_COLOR: Color = Color::rgb(0.08, 0.1, 0.12);
 pub(crate) const COMMON_FONT_SIZE: f32 = 18.;
 pub(crate) const MIDDLE_FONT_SIZE: f32 = 24.;
 pub(crate) const SIDE_MARGIN_PX: f32 = 10.;
 pub(crate) const Z_COORDINATE: f32 = 10.;
+pub(crate) const FONT_CJK_LIGHT: &str = "fonts/NotoSansSC-Light.otf";
+pub(crate) const FONT_CJK_MEDIUM: &str = "fonts/NotoSansSC-Medium.otf";
+pub(crate) const FONT_LATIN: &str = "fonts/FiraSans-Medium.ttf";
 // endregion: -- OTHER
 
 // region: --- PLAYER
 pub(crate) const PLAYER_SPRITE: &str = "player_a_01.png";
 pub(crate) const PLAYER_SIZE: (f32, f32) = (144., 75.);
+// nose, wings and body, fitted to the opaque pixels of `PLAYER_SPRITE`
+pub(crate) const PLAYER_HITBOX: &[Hitbox] = &[
+    Hitbox::aabb((1., 30.5), (42., 12.)),
+    Hitbox::aabb((0., 10.5), (140., 20.)),
+    Hitbox::aabb((0., -4.), (84., 57.)),
+];
 pub(crate) const PLAYER_LASER_SPRITE: &str = "laser_a_01.png";
 pub(crate) const PLAYER_LASER_SIZE: (f32, f32) = (9., 54.);
+pub(crate) const PLAYER_LASER_HITBOX: &[Hitbox] = &[Hitbox::aabb((0., 0.), PLAYER_LASER_SIZE)];
 pub(crate) const PLAYER_RESPAWN_DELAY: f64 = 2.;
 pub(crate) const PLAYER_MAX_LIVES: u32 = 5;
+pub(crate) const PLAYER_HARD_LIVES: u32 = 3;
 pub(crate) const PLAYER_INVINCIBLE_DURATION: Duration = Duration::from_secs(3);
 // endregion: --- PLAYER
 
 // region: --- ENEMY
 pub(crate) const ENEMY_SPRITE: &str = "enemy_a_01.png";
-pub(crate) const ENEMY_SIZE: (f32, f32) = (144., 75.);
+pub(crate) const ENEMY_SIZE: (f32, f32) = (93., 84.);
+// round body and two legs, fitted to the opaque pixels of `ENEMY_SPRITE`
+pub(crate) const ENEMY_HITBOX: &[Hitbox] = &[
+    Hitbox::circle((0., 12.), 36.),
+    Hitbox::aabb((-20.5, -26.), (24., 28.)),
+    Hitbox::aabb((20.5, -26.), (24., 28.)),
+];
 pub(crate) const ENEMY_LASER_SPRITE: &str = "laser_b_01.png";
+pub(crate) const ENEMY_ELITE_CHANCE: f64 = 0.2;
+pub(crate) const ENEMY_ELITE_COLOR: Color = Color::rgb(1., 0.45, 0.45);
 pub(crate) const ENEMY_LASER_SIZE: (f32, f32) = (17., 55.);
+pub(crate) const ENEMY_LASER_HITBOX: &[Hitbox] = &[Hitbox::aabb((0., 0.), ENEMY_LASER_SIZE)];
 // endregion --- ENEMY
 
 // region: --- GAME
 pub(crate) const EXPLOSION_SHEET: &str = "explo_a_sheet.png";
 pub(crate) const EXPLOSION_LEN: usize = 16;
+pub(crate) const SPARK_SIZE: f32 = 14.;
+pub(crate) const SPARK_DURATION: f32 = 0.25;
 pub(crate) const SPRITE_SCALE: f32 = 0.5;
 pub(crate) const TIME_STEP: f32 = 1. / 60.;
 pub(crate) const BASE_SPEED: f32 = 500.;
 pub(crate) const FORMATION_MEMBERS_MAX: u32 = 2;
 pub(crate) const HISTORY_LEN: usize = 4;
+pub(crate) const NAME_LEN: usize = 3;
+pub(crate) const NAME_CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+pub(crate) const CONSOLE_LINES: usize = 8;
 // endregion: --- GAME
 
+// region: --- SCORE
+pub(crate) const ENEMY_GRUNT_POINTS: u32 = 10;
+pub(crate) const ENEMY_ELITE_POINTS: u32 = 30;
+pub(crate) const FORMATION_BONUS: u32 = 50;
+pub(crate) const INTERCEPT_BONUS: u32 = 5;
+pub(crate) const COMBO_STEP: u32 = 5;
+pub(crate) const COMBO_MAX_MULTIPLIER: u32 = 4;
+pub(crate) const COMBO_DECAY: Duration = Duration::from_secs(2);
+pub(crate) const SCORE_POPUP_DURATION: f32 = 0.8;
+pub(crate) const SCORE_POPUP_SPEED: f32 = 60.;
+pub(crate) const ACHIEVEMENT_RUN_KILLS: u32 = 100;
+pub(crate) const ACHIEVEMENT_TOAST_DURATION: f32 = 3.;
+// endregion: --- SCORE
+
+// region: --- COLLISION
+pub(crate) const BROADPHASE_CELL: f32 = 64.;
+// sprites fly in from outside the playfield
+pub(crate) const BROADPHASE_MARGIN: f32 = 200.;
+pub(crate) const LAYER_PLAYER: u32 = 1 << 0;
+pub(crate) const LAYER_PLAYER_LASER: u32 = 1 << 1;
+pub(crate) const LAYER_ENEMY: u32 = 1 << 2;
+pub(crate) const LAYER_ENEMY_LASER: u32 = 1 << 3;
+// endregion: --- COLLISION
+
+// region: --- DIRECTOR
+pub(crate) const DIRECTOR_INTERVAL: Duration = Duration::from_secs(5);
+pub(crate) const DIRECTOR_RANGE: f32 = 0.5;
+pub(crate) const DIRECTOR_STEP: f32 = 0.1;
+// share of the recent performance kept at every interval
+pub(crate) const DIRECTOR_MEMORY: f32 = 0.6;
+// endregion: --- DIRECTOR
+
 // region --- ICONS
 pub(crate) const ICONS_VOICE_ON: &str = "icons/volice_on.png";
 pub(crate) const ICONS_VOICE_OFF: &str = "icons/volice_off.png";
@@ -47,3 +110,15 @@ pub(crate) const AUDIOS_SHOT_LOW: &str = "audios/shot-low.wav";
 pub(crate) const AUDIOS_SHOT_MID: &str = "audios/shot-mid.wav";
 pub(crate) const AUDIOS_SHOT_HIGH: &str = "audios/shot-high.wav";
 // endregion --- AUDIOS
+
+// region --- MUSIC
+pub(crate) const MUSIC_CALM: &str = "audios/music-calm.wav";
+pub(crate) const MUSIC_BATTLE: &str = "audios/music-battle.wav";
+pub(crate) const MUSIC_BOSS: &str = "audios/music-boss.wav";
+pub(crate) const MUSIC_GAME_OVER: &str = "audios/music-game-over.wav";
+pub(crate) const MUSIC_VOLUME: f32 = 0.5;
+pub(crate) const MUSIC_CROSSFADE: Duration = Duration::from_millis(1500);
+pub(crate) const MUSIC_DUCK_VOLUME: f32 = 0.3;
+pub(crate) const MUSIC_DUCK_DURATION: Duration = Duration::from_millis(1800);
+pub(crate) const VOLUME_STEP: f32 = 0.1;
+// endregion --- MUSIC
@@ -0,0 +1,342 @@
+use std::{f32::consts::TAU, time::SystemTime};
+
+use bevy::{
+    diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use crate::{
+    components::{
+        Collider, ColliderShape, DebugText, Enemy, Explosion, FormationPath, FromPlayer,
+        HitboxDrawn, HitboxGizmo, Laser, PlayfieldRoot, PoolKind, Pooled, TextFonts,
+    },
+    consts::{FORMATION_MEMBERS_MAX, SIDE_MARGIN_PX},
+    enemy::{Formation, FormationMaker},
+    entity::{AppState, Director, EnemyState, GameState, PlayerState, Settings},
+    fonts::{FontRegistry, FontStyle},
+    locale::Locale,
+    pool::Pools,
+};
+
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(FrameTimeDiagnosticsPlugin)
+            .insert_resource(DebugOverlay::default())
+            .add_system(debug_spawn_system.in_schedule(OnExit(AppState::Loading)))
+            .add_system(debug_toggle_system)
+            .add_system(debug_text_system.after(debug_toggle_system))
+            .add_system(formation_path_draw_system.after(debug_toggle_system))
+            // after the frame's despawns are applied, so no gizmo is attached to a dead collider
+            .add_system(
+                hitbox_draw_system
+                    .after(debug_toggle_system)
+                    .in_base_set(CoreSet::PostUpdate),
+            );
+    }
+}
+
+/// Resource - whether the debug overlay (F3) and the hitboxes (F4) are shown
+#[derive(Default, Resource)]
+pub struct DebugOverlay {
+    pub visible: bool,
+    pub hitboxes: bool,
+}
+
+fn debug_spawn_system(
+    mut commands: Commands,
+    fonts: Res<FontRegistry>,
+    locale: Res<Locale>,
+    root_query: Query<Entity, With<PlayfieldRoot>>,
+) {
+    commands
+        .spawn(
+            TextBundle::from_sections([TextSection::new(
+                "",
+                fonts.style(&locale, FontStyle::Hud, Color::LIME_GREEN),
+            )])
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: Val::Px(SIDE_MARGIN_PX),
+                    left: Val::Px(SIDE_MARGIN_PX),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(TextFonts(vec![FontStyle::Hud]))
+        .insert(Visibility::Hidden)
+        .insert(DebugText)
+        .set_parent(root_query.single());
+}
+
+fn debug_toggle_system(
+    kb: Res<Input<KeyCode>>,
+    mut overlay: ResMut<DebugOverlay>,
+    mut query: Query<&mut Visibility, With<DebugText>>,
+) {
+    if kb.just_pressed(KeyCode::F4) {
+        overlay.hitboxes = !overlay.hitboxes;
+    }
+    if kb.just_pressed(KeyCode::F3) {
+        overlay.visible = !overlay.visible;
+        for mut visibility in &mut query {
+            *visibility = if overlay.visible {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
+
+fn debug_text_system(
+    overlay: Res<DebugOverlay>,
+    diagnostics: Res<Diagnostics>,
+    time: Res<Time>,
+    state: Res<State<AppState>>,
+    settings: Res<Settings>,
+    game_state: Res<GameState>,
+    enemy_state: Res<EnemyState>,
+    player_state: Res<PlayerState>,
+    formation_maker: Res<FormationMaker>,
+    director: Res<Director>,
+    pools: Res<Pools>,
+    laser_query: Query<(&Pooled, Option<&FromPlayer>), With<Laser>>,
+    explosion_query: Query<&Pooled, With<Explosion>>,
+    mut query: Query<&mut Text, With<DebugText>>,
+) {
+    if !overlay.visible {
+        return;
+    }
+
+    let mut lines = Vec::new();
+    let smoothed = |id| {
+        diagnostics
+            .get(id)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or_default()
+    };
+    lines.push(format!(
+        "fps {:.0}  frame {:.2} ms  state {:?}  difficulty {:?}  timescale {}",
+        smoothed(FrameTimeDiagnosticsPlugin::FPS),
+        smoothed(FrameTimeDiagnosticsPlugin::FRAME_TIME),
+        state.0,
+        game_state.difficulty,
+        time.relative_speed(),
+    ));
+
+    let (mut player_lasers, mut enemy_lasers) = (0, 0);
+    for (_, from_player) in laser_query.iter().filter(|(pooled, _)| pooled.active) {
+        if from_player.is_some() {
+            player_lasers += 1;
+        } else {
+            enemy_lasers += 1;
+        }
+    }
+    let explosions = explosion_query
+        .iter()
+        .filter(|pooled| pooled.active)
+        .count();
+    lines.push(format!(
+        "enemies {}/{}  level {:?} ({} per level)  lasers {}/{}  explosions {}",
+        enemy_state.count,
+        director.spawn_cap(game_state.difficulty.level_count(enemy_state.level_count)),
+        enemy_state.level,
+        enemy_state.level_count,
+        player_lasers,
+        enemy_lasers,
+        explosions,
+    ));
+
+    let invincible_left = game_state
+        .difficulty
+        .invincible_duration()
+        .saturating_sub(
+            SystemTime::now()
+                .duration_since(player_state.born)
+                .unwrap_or_default(),
+        )
+        .as_secs_f32();
+    lines.push(format!(
+        "player: {}  lives {}  points {}  kills {}/{}  fire {:?}  invincible {}{}{}",
+        if player_state.on { "on" } else { "off" },
+        player_state.lives,
+        player_state.points,
+        player_state.current_score,
+        player_state.total_score,
+        player_state.get_fire_level(),
+        if player_state.invincible && invincible_left > 0. {
+            format!("{:.1}s", invincible_left)
+        } else {
+            "no".to_string()
+        },
+        if game_state.god { "  god" } else { "" },
+        if game_state.cheated { "  cheated" } else { "" },
+    ));
+
+    match formation_maker.template() {
+        Some((tmpl, members)) => lines.push(format!(
+            "formation #{} ({}/{})  pivot ({:.0}, {:.0})  radius ({:.0}, {:.0})  speed {:.0}",
+            tmpl.id,
+            members,
+            FORMATION_MEMBERS_MAX,
+            tmpl.pivot.0,
+            tmpl.pivot.1,
+            tmpl.radius.0,
+            tmpl.radius.1,
+            tmpl.speed,
+        )),
+        None => lines.push("formation: none yet".to_string()),
+    }
+    let adaptive = if settings.adaptive { "on" } else { "off" };
+    lines.push(format!(
+        "director ({}): {:+.2}  fire x{:.2}  speed x{:.2}  spawn x{:.2}",
+        adaptive,
+        director.adjustment,
+        director.fire_scale(),
+        director.speed_scale(),
+        director.spawn_scale(),
+    ));
+    lines.push(format!(
+        "  accuracy {:.0}%  deaths {:.1}  last hit {:.0}s ago",
+        director.accuracy() * 100.,
+        director.deaths,
+        director.since_hit,
+    ));
+    lines.push(format!(
+        "pool: {} spawned  {} reused  parked {}/{}/{} (player lasers/enemy lasers/explosions)",
+        pools.spawned,
+        pools.reused,
+        pools.parked(PoolKind::PlayerLaser),
+        pools.parked(PoolKind::EnemyLaser),
+        pools.parked(PoolKind::Explosion),
+    ));
+
+    let txt = lines.join("\n");
+    for mut text in &mut query {
+        if text.sections[0].value != txt {
+            text.sections[0].value = txt.clone();
+        }
+    }
+}
+
+/// Translucent shapes parented to every collider, so they follow its transform
+fn hitbox_draw_system(
+    mut commands: Commands,
+    overlay: Res<DebugOverlay>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut handles: Local<Option<(Handle<Mesh>, Handle<Mesh>, Handle<ColorMaterial>)>>,
+    new_query: Query<(Entity, &Collider), Without<HitboxDrawn>>,
+    drawn_query: Query<Entity, With<HitboxDrawn>>,
+    collider_query: Query<(), With<Collider>>,
+    gizmo_query: Query<(Entity, &Parent), With<HitboxGizmo>>,
+) {
+    // a plain `despawn` of the collider leaves its gizmos behind
+    for (gizmo, parent) in gizmo_query.iter() {
+        if !overlay.hitboxes || !collider_query.contains(parent.get()) {
+            commands.entity(gizmo).despawn();
+        }
+    }
+
+    if !overlay.hitboxes {
+        for entity in drawn_query.iter() {
+            commands.entity(entity).remove::<HitboxDrawn>();
+        }
+        return;
+    }
+
+    let (quad, circle, material) = handles
+        .get_or_insert_with(|| {
+            (
+                meshes.add(shape::Quad::new(Vec2::ONE).into()),
+                meshes.add(shape::Circle::new(1.).into()),
+                materials.add(ColorMaterial::from(Color::rgba(1., 0., 1., 0.4))),
+            )
+        })
+        .clone();
+
+    for (entity, collider) in new_query.iter() {
+        commands
+            .entity(entity)
+            .insert(HitboxDrawn)
+            .with_children(|parent| {
+                for hitbox in collider.hitboxes.iter() {
+                    let (mesh, size) = match hitbox.shape {
+                        ColliderShape::Aabb(size) => (quad.clone(), size),
+                        ColliderShape::Circle(radius) => (circle.clone(), Vec2::splat(radius)),
+                    };
+                    parent
+                        .spawn(ColorMesh2dBundle {
+                            mesh: mesh.into(),
+                            material: material.clone(),
+                            transform: Transform {
+                                translation: hitbox.offset.extend(1.),
+                                scale: size.extend(1.),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        })
+                        .insert(HitboxGizmo);
+                }
+            });
+    }
+}
+
+/// Dotted ellipse of every formation that still has members, while the overlay is shown
+fn formation_path_draw_system(
+    mut commands: Commands,
+    overlay: Res<DebugOverlay>,
+    enemy_query: Query<&Formation, With<Enemy>>,
+    path_query: Query<(Entity, &FormationPath)>,
+) {
+    const DOTS: usize = 48;
+
+    let mut formations: HashMap<u32, &Formation> = HashMap::new();
+    if overlay.visible {
+        for formation in enemy_query.iter() {
+            formations.entry(formation.id).or_insert(formation);
+        }
+    }
+
+    // drop the paths of the formations that are gone
+    let mut drawn = HashSet::new();
+    for (entity, path) in path_query.iter() {
+        if formations.contains_key(&path.0) {
+            drawn.insert(path.0);
+        } else {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    for (id, formation) in formations {
+        if drawn.contains(&id) {
+            continue;
+        }
+
+        let (x_pivot, y_pivot) = formation.pivot;
+        let (x_radius, y_radius) = formation.radius;
+        for i in 0..DOTS {
+            let angle = i as f32 / DOTS as f32 * TAU;
+            commands
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::LIME_GREEN.with_a(0.6),
+                        custom_size: Some(Vec2::splat(3.)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(
+                        x_radius * angle.cos() + x_pivot,
+                        y_radius * angle.sin() + y_pivot,
+                        1.,
+                    ),
+                    ..Default::default()
+                })
+                .insert(FormationPath(id));
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::{prelude::*, time::common_conditions::on_timer};
+
+use crate::{
+    consts::{self, DIRECTOR_RANGE},
+    entity::{AppState, Director, GameState, Settings},
+    events::{EnemyKilled, GameSet, LaserFired, PlayerHit},
+};
+
+pub struct DirectorPlugin;
+
+impl Plugin for DirectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Director::default())
+            .add_system(director_event_system.in_set(GameSet::React))
+            .add_system(director_settings_system)
+            .add_systems(
+                (
+                    director_clock_system,
+                    director_adjust_system.run_if(on_timer(consts::DIRECTOR_INTERVAL)),
+                )
+                    .in_set(OnUpdate(AppState::InGame)),
+            );
+    }
+}
+
+fn director_event_system(
+    mut director: ResMut<Director>,
+    mut fired_events: EventReader<LaserFired>,
+    mut killed_events: EventReader<EnemyKilled>,
+    mut hit_events: EventReader<PlayerHit>,
+) {
+    for fired in fired_events.iter() {
+        director.shots += fired.count as f32;
+    }
+    director.hits += killed_events.iter().filter(|killed| !killed.rammed).count() as f32;
+    for _ in hit_events.iter() {
+        director.deaths += 1.;
+        director.since_hit = 0.;
+    }
+}
+
+fn director_clock_system(
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    mut director: ResMut<Director>,
+) {
+    if !game_state.is_over {
+        director.since_hit += time.delta_seconds();
+    }
+}
+
+/// Turning the director off takes effect at once, not at the next interval
+fn director_settings_system(settings: Res<Settings>, mut director: ResMut<Director>) {
+    if settings.is_changed() && !settings.adaptive {
+        director.adjustment = 0.;
+    }
+}
+
+/// Move the adjustment one step towards how well the player is doing
+fn director_adjust_system(settings: Res<Settings>, mut director: ResMut<Director>) {
+    if !settings.adaptive {
+        director.adjustment = 0.;
+        return;
+    }
+
+    // hitting the enemies and staying alive push up, dying pushes down
+    let survival = (director.since_hit / 60.).min(1.);
+    let skill = (director.accuracy() - 0.3) + survival / 2. - director.deaths * 0.3;
+    let target = skill.clamp(-DIRECTOR_RANGE, DIRECTOR_RANGE);
+
+    let step = (target - director.adjustment).clamp(-consts::DIRECTOR_STEP, consts::DIRECTOR_STEP);
+    director.adjustment += step;
+
+    // older performance counts less and less
+    director.shots *= consts::DIRECTOR_MEMORY;
+    director.hits *= consts::DIRECTOR_MEMORY;
+    director.deaths *= consts::DIRECTOR_MEMORY;
+}
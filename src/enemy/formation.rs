@@ -1,15 +1,18 @@
-use bevy::prelude::*;
-use rand::{thread_rng, Rng};
+use bevy::{prelude::*, utils::HashMap};
+use rand::Rng;
 
 use crate::{
     consts::FORMATION_MEMBERS_MAX,
     consts::{self},
+    utils::{self, RngStream},
     WinSize,
 };
 
 /// Componet - Enemy Formation (per enemy)
 #[derive(Clone, Component)]
 pub struct Formation {
+    // members made from the same template share the id
+    pub id: u32,
     pub start: (f32, f32),
     pub radius: (f32, f32),
     pub pivot: (f32, f32),
@@ -22,10 +25,20 @@ pub struct Formation {
 pub struct FormationMaker {
     current_template: Option<Formation>,
     current_members: u32,
+    next_id: u32,
+    // members spawned and killed of every formation still on the field, by id
+    tallies: HashMap<u32, (u32, u32)>,
 }
 
 /// Formation factory implemention
 impl FormationMaker {
+    /// The template new enemies copy, with how many members it already has
+    pub fn template(&self) -> Option<(&Formation, u32)> {
+        self.current_template
+            .as_ref()
+            .map(|tmpl| (tmpl, self.current_members))
+    }
+
     pub fn make(&mut self, win_size: &WinSize) -> Formation {
         match (
             &self.current_template,
@@ -34,10 +47,11 @@ impl FormationMaker {
             // if has current template and still within max memebers
             (Some(tmpl), false) => {
                 self.current_members += 1;
+                self.tallies.entry(tmpl.id).or_default().0 += 1;
                 tmpl.clone()
             }
             (None, _) | (_, true) => {
-                let mut rng = thread_rng();
+                let mut rng = utils::rng(RngStream::Spawn);
 
                 // compute the start x/y
                 let w_span = win_size.w / 2. + 100.;
@@ -60,9 +74,11 @@ impl FormationMaker {
                 // speed (fixed for now)
                 let speed = consts::BASE_SPEED;
 
+                self.next_id += 1;
                 let formation = Formation {
+                    id: self.next_id,
                     start,
-                    radius: radius,
+                    radius,
                     pivot,
                     speed,
                     angle,
@@ -72,9 +88,70 @@ impl FormationMaker {
                 self.current_template = Some(formation.clone());
                 // reset the memebers to 1
                 self.current_members = 1;
+                self.tallies.insert(formation.id, (1, 0));
 
                 formation
             }
         }
     }
+
+    /// Counts a killed member, true when it was the last one of a formation of two or more.
+    /// A cleared formation takes no more members, even when the spawn cap cut it short
+    pub fn killed(&mut self, id: u32) -> bool {
+        let Some((spawned, killed)) = self.tallies.get_mut(&id) else {
+            return false;
+        };
+        *killed += 1;
+        if killed < spawned {
+            return false;
+        }
+        let spawned = *spawned;
+
+        self.tallies.remove(&id);
+        if self
+            .current_template
+            .as_ref()
+            .is_some_and(|tmpl| tmpl.id == id)
+        {
+            self.current_template = None;
+        }
+        // a lone enemy is no formation
+        spawned >= 2
+    }
+
+    /// A new run starts new formations, the survivors of the last one earn no bonus
+    pub fn reset(&mut self) {
+        self.current_template = None;
+        self.current_members = 0;
+        self.tallies.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIN_SIZE: WinSize = WinSize { w: 600., h: 800. };
+
+    #[test]
+    fn killed_pays_for_a_cleared_formation() {
+        let mut maker = FormationMaker::default();
+        let id = maker.make(&WIN_SIZE).id;
+        maker.make(&WIN_SIZE);
+
+        assert!(!maker.killed(id));
+        assert!(maker.killed(id));
+        // cleared, the next member starts a new formation
+        assert_ne!(maker.make(&WIN_SIZE).id, id);
+    }
+
+    #[test]
+    fn killed_pays_nothing_for_a_lone_member() {
+        let mut maker = FormationMaker::default();
+        let id = maker.make(&WIN_SIZE).id;
+
+        assert!(!maker.killed(id));
+        assert!(!maker.killed(id));
+        assert_ne!(maker.make(&WIN_SIZE).id, id);
+    }
 }
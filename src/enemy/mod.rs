@@ -1,15 +1,21 @@
 use std::{f32::consts::PI, time::Duration};
 
 use bevy::{prelude::*, time::common_conditions::on_timer};
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
 use crate::{
-    components::{Enemy, FromEnemy, Laser, Movable, SpriteSize, Velocity},
+    components::{
+        Collider, Enemy, EnemyKind, FromEnemy, Interceptible, Laser, Movable, PoolKind, Pooled,
+        SpriteSize, Velocity,
+    },
     consts::{self},
-    entity::{EnemyState, GameTextures, WinSize},
+    entity::{AppState, Director, EnemyState, GameState, GameTextures, WinSize},
+    events::{EnemyKilled, GameSet},
+    pool::Pools,
+    utils::{self, RngStream},
 };
 
-use self::formation::{Formation, FormationMaker};
+pub use self::formation::{Formation, FormationMaker};
 
 mod formation;
 
@@ -18,18 +24,21 @@ pub struct EnemyPlugin;
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(FormationMaker::default())
-            .add_system(enemy_spawn_system.run_if(on_timer(Duration::from_millis(250))))
-            .add_system(enemy_fire_system.run_if(enemy_fire_criteria))
-            .add_system(enemy_movement_system);
+            .add_systems(
+                (
+                    enemy_spawn_system.run_if(on_timer(Duration::from_millis(250))),
+                    enemy_fire_system.run_if(enemy_fire_criteria),
+                    enemy_movement_system,
+                )
+                    .in_set(OnUpdate(AppState::InGame)),
+            )
+            .add_system(enemy_killed_system.in_set(GameSet::React));
     }
 }
 
-fn enemy_fire_criteria() -> bool {
-    if thread_rng().gen_bool(1. / 60.) {
-        true
-    } else {
-        false
-    }
+fn enemy_fire_criteria(game_state: Res<GameState>, director: Res<Director>) -> bool {
+    let chance = game_state.difficulty.enemy_fire_chance() * director.fire_scale() as f64;
+    utils::rng(RngStream::EnemyFire).gen_bool(chance.min(1.))
 }
 
 fn enemy_spawn_system(
@@ -37,43 +46,90 @@ fn enemy_spawn_system(
     game_textures: Res<GameTextures>,
     mut enemy_state: ResMut<EnemyState>,
     mut formation_maker: ResMut<FormationMaker>,
+    game_state: Res<GameState>,
+    director: Res<Director>,
     win_size: Res<WinSize>,
 ) {
-    if enemy_state.count < enemy_state.level_count {
-        // get formation and start x/y
-        let formation = formation_maker.make(&win_size);
-        let (x, y) = formation.start;
-
-        commands
-            .spawn(SpriteBundle {
-                texture: game_textures.enemy.clone(),
-                transform: Transform {
-                    translation: Vec3::new(x, y, consts::Z_COORDINATE),
-                    scale: Vec3::new(consts::SPRITE_SCALE, consts::SPRITE_SCALE, 1.),
-                    rotation: Quat::from_rotation_x(PI),
-                    ..Default::default()
-                },
+    let level_count = game_state.difficulty.level_count(enemy_state.level_count);
+    if enemy_state.count < director.spawn_cap(level_count) {
+        spawn_enemy(
+            &mut commands,
+            &game_textures,
+            &mut enemy_state,
+            &mut formation_maker,
+            &win_size,
+        );
+    }
+}
+
+/// One enemy of the current formation, also spawned on demand by the dev console
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    game_textures: &GameTextures,
+    enemy_state: &mut EnemyState,
+    formation_maker: &mut FormationMaker,
+    win_size: &WinSize,
+) {
+    // get formation and start x/y
+    let formation = formation_maker.make(win_size);
+    let (x, y) = formation.start;
+
+    // a few elites, tinted and worth more points
+    let kind = if utils::rng(RngStream::Spawn).gen_bool(consts::ENEMY_ELITE_CHANCE) {
+        EnemyKind::Elite
+    } else {
+        EnemyKind::Grunt
+    };
+    let color = match kind {
+        EnemyKind::Grunt => Color::WHITE,
+        EnemyKind::Elite => consts::ENEMY_ELITE_COLOR,
+    };
+
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color,
                 ..Default::default()
-            })
-            .insert(Enemy)
-            .insert(formation)
-            .insert(SpriteSize::from(consts::ENEMY_SIZE));
+            },
+            texture: game_textures.enemy.clone(),
+            transform: Transform {
+                translation: Vec3::new(x, y, consts::Z_COORDINATE),
+                scale: Vec3::new(consts::SPRITE_SCALE, consts::SPRITE_SCALE, 1.),
+                rotation: Quat::from_rotation_x(PI),
+            },
+            ..Default::default()
+        })
+        .insert(Enemy)
+        .insert(kind)
+        .insert(formation)
+        .insert(SpriteSize::from(consts::ENEMY_SIZE))
+        .insert(Collider::new(consts::ENEMY_HITBOX, consts::LAYER_ENEMY, 0));
+
+    enemy_state.count += 1;
+}
 
-        enemy_state.count += 1;
+fn enemy_killed_system(
+    mut enemy_state: ResMut<EnemyState>,
+    mut killed_events: EventReader<EnemyKilled>,
+) {
+    for _ in killed_events.iter() {
+        enemy_state.count -= 1;
     }
 }
 
 fn enemy_fire_system(
     mut commands: Commands,
+    mut pools: ResMut<Pools>,
     game_textures: Res<GameTextures>,
     enemy_state: ResMut<EnemyState>,
-    enemy_query: Query<&Transform, With<Enemy>>,
+    game_state: Res<GameState>,
+    enemy_query: Query<(&Transform, &EnemyKind), With<Enemy>>,
 ) {
-    for tf in enemy_query.iter() {
+    for (tf, kind) in enemy_query.iter() {
         let (x, y) = (tf.translation.x, tf.translation.y);
         // spawn enemy laser sprite;
-        commands
-            .spawn(SpriteBundle {
+        pools.acquire(&mut commands, PoolKind::EnemyLaser).insert((
+            SpriteBundle {
                 texture: game_textures.enemy_laser.clone(),
                 transform: Transform {
                     translation: Vec3::new(x, y - 15., 0.),
@@ -81,25 +137,38 @@ fn enemy_fire_system(
                     ..Default::default()
                 },
                 ..Default::default()
-            })
-            .insert(Laser)
-            .insert(SpriteSize::from(consts::ENEMY_LASER_SIZE))
-            .insert(FromEnemy)
-            .insert(Movable { auto_despawn: true })
-            .insert(Velocity {
+            },
+            Laser,
+            SpriteSize::from(consts::ENEMY_LASER_SIZE),
+            Collider::new(
+                consts::ENEMY_LASER_HITBOX,
+                consts::LAYER_ENEMY_LASER,
+                consts::LAYER_PLAYER,
+            ),
+            FromEnemy,
+            Movable { auto_despawn: true },
+            Velocity {
                 x: 0.,
-                y: enemy_state.velocity,
-            });
+                y: enemy_state.velocity * game_state.difficulty.enemy_scale(),
+            },
+            Pooled::new(PoolKind::EnemyLaser),
+            // elite lasers can't be shot down
+            Interceptible(matches!(kind, EnemyKind::Grunt)),
+        ));
     }
 }
-
-fn enemy_movement_system(mut query: Query<(&mut Transform, &mut Formation), With<Enemy>>) {
+fn enemy_movement_system(
+    time: Res<Time>,
+    director: Res<Director>,
+    mut query: Query<(&mut Transform, &mut Formation), With<Enemy>>,
+) {
     for (mut transform, mut formation) in query.iter_mut() {
         // current position
         let (x_org, y_org) = (transform.translation.x, transform.translation.y);
 
         // max distance
-        let max_distance = consts::TIME_STEP * formation.speed;
+        let speed = formation.speed * director.speed_scale() * time.relative_speed();
+        let max_distance = consts::TIME_STEP * speed;
 
         // fixeture (hardcode for now)
         let dir: f32 = if formation.start.0 < 0. { 1. } else { -1. }; // -1 for counter clockwise, -1 clockwise
@@ -107,8 +176,8 @@ fn enemy_movement_system(mut query: Query<(&mut Transform, &mut Formation), With
         let (x_radius, y_radius) = formation.radius;
 
         // compute next angle (based on time for now)
-        let angle = formation.angle
-            + dir * formation.speed * consts::TIME_STEP / (x_radius.min(y_radius) * PI / 2.);
+        let angle =
+            formation.angle + dir * speed * consts::TIME_STEP / (x_radius.min(y_radius) * PI / 2.);
         let x_dst = x_radius * angle.cos() + x_pivot;
         let y_dst = y_radius * angle.sin() + y_pivot;
 
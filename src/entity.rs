@@ -1,8 +1,13 @@
-use std::time::SystemTime;
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::consts::{self, PLAYER_MAX_LIVES};
+use crate::consts::{self, PLAYER_HARD_LIVES, PLAYER_MAX_LIVES};
 
 #[derive(Resource)]
 pub struct WinSize {
@@ -10,6 +15,19 @@ pub struct WinSize {
     pub h: f32,
 }
 
+/// Resource - where bounded entities may move, the whole playfield by default
+#[derive(Resource)]
+pub struct PlayArea(pub Rect);
+
+impl From<&WinSize> for PlayArea {
+    fn from(win_size: &WinSize) -> Self {
+        PlayArea(Rect::from_center_size(
+            Vec2::ZERO,
+            Vec2::new(win_size.w, win_size.h),
+        ))
+    }
+}
+
 #[derive(Resource)]
 pub struct GameTextures {
     pub player: Handle<Image>,
@@ -20,8 +38,36 @@ pub struct GameTextures {
     // score: Handle<>
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Resource)]
+pub struct GameAudio {
+    pub explosion: Handle<AudioSource>,
+    pub level_upgrade: Handle<AudioSource>,
+    pub player_fail: Handle<AudioSource>,
+    pub shot_low: Handle<AudioSource>,
+    pub shot_mid: Handle<AudioSource>,
+    pub shot_high: Handle<AudioSource>,
+    pub music_calm: Handle<AudioSource>,
+    pub music_battle: Handle<AudioSource>,
+    pub music_boss: Handle<AudioSource>,
+    pub music_game_over: Handle<AudioSource>,
+    pub voice_on: Handle<Image>,
+    pub voice_off: Handle<Image>,
+}
+
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AppState {
+    #[default]
+    Loading,
+    InGame,
+    Settings,
+    NameEntry,
+    Achievements,
+    Console,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum GameLevel {
+    #[default]
     Basic,
     Middle,
     Strong,
@@ -29,6 +75,34 @@ pub enum GameLevel {
     Invincible,
 }
 
+impl GameLevel {
+    pub const ALL: [GameLevel; 5] = [
+        GameLevel::Basic,
+        GameLevel::Middle,
+        GameLevel::Strong,
+        GameLevel::Powerful,
+        GameLevel::Invincible,
+    ];
+
+    /// Kills it takes to reach the level
+    pub fn min_kills(&self) -> u32 {
+        match self {
+            GameLevel::Basic => 0,
+            GameLevel::Middle => 10,
+            GameLevel::Strong => 30,
+            GameLevel::Powerful => 60,
+            GameLevel::Invincible => 100,
+        }
+    }
+
+    /// Case-insensitive, as printed by `Debug`
+    pub fn from_name(name: &str) -> Option<GameLevel> {
+        Self::ALL
+            .into_iter()
+            .find(|level| format!("{:?}", level).eq_ignore_ascii_case(name))
+    }
+}
+
 #[derive(Resource)]
 pub struct EnemyState {
     pub count: u32,
@@ -37,15 +111,353 @@ pub struct EnemyState {
     pub velocity: f32,
 }
 
-#[derive(Resource)]
+#[derive(Default, Resource)]
 pub struct GameState {
     pub show_over: bool,
     pub is_over: bool,
+    // picked in the settings, takes effect when a run starts
+    pub difficulty: Difficulty,
+    // dev console `god`, kept across runs
+    pub god: bool,
+    // the dev console changed this run, so it is left out of the leaderboard, career and achievements
+    pub cheated: bool,
 }
 
-#[derive(Resource)]
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    Fullscreen,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+impl Difficulty {
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Nightmare,
+            Difficulty::Nightmare => Difficulty::Easy,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Nightmare,
+            Difficulty::Normal => Difficulty::Easy,
+            Difficulty::Hard => Difficulty::Normal,
+            Difficulty::Nightmare => Difficulty::Hard,
+        }
+    }
+
+    pub fn lives(&self) -> u32 {
+        match self {
+            Difficulty::Easy => PLAYER_MAX_LIVES + 2,
+            Difficulty::Normal => PLAYER_MAX_LIVES,
+            Difficulty::Hard => PLAYER_HARD_LIVES,
+            Difficulty::Nightmare => 1,
+        }
+    }
+
+    pub fn respawn_delay(&self) -> f64 {
+        match self {
+            Difficulty::Easy => consts::PLAYER_RESPAWN_DELAY * 0.75,
+            Difficulty::Normal => consts::PLAYER_RESPAWN_DELAY,
+            Difficulty::Hard => consts::PLAYER_RESPAWN_DELAY * 1.25,
+            Difficulty::Nightmare => consts::PLAYER_RESPAWN_DELAY * 1.5,
+        }
+    }
+
+    pub fn invincible_duration(&self) -> Duration {
+        match self {
+            Difficulty::Easy => consts::PLAYER_INVINCIBLE_DURATION * 4 / 3,
+            Difficulty::Normal => consts::PLAYER_INVINCIBLE_DURATION,
+            Difficulty::Hard => consts::PLAYER_INVINCIBLE_DURATION * 2 / 3,
+            Difficulty::Nightmare => consts::PLAYER_INVINCIBLE_DURATION / 3,
+        }
+    }
+
+    /// Chance per frame that the enemies fire
+    pub fn enemy_fire_chance(&self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.5 / 60.,
+            Difficulty::Normal => 1. / 60.,
+            Difficulty::Hard => 1.5 / 60.,
+            Difficulty::Nightmare => 2. / 60.,
+        }
+    }
+
+    /// Scale of the enemy laser speed and of the enemies per level
+    pub fn enemy_scale(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.,
+            Difficulty::Hard => 1.25,
+            Difficulty::Nightmare => 1.5,
+        }
+    }
+
+    pub fn level_count(&self, level_count: u32) -> u32 {
+        ((level_count as f32 * self.enemy_scale()).round() as u32).max(1)
+    }
+}
+
+/// Resource - the adaptive director, nudges the enemies by `adjustment`
+/// (within `±DIRECTOR_RANGE`) after the recent performance of the player
+#[derive(Resource, Default)]
+pub struct Director {
+    pub adjustment: f32,
+    // recent shots, hits and deaths, fading out over time
+    pub shots: f32,
+    pub hits: f32,
+    pub deaths: f32,
+    // seconds since the ship was last hit
+    pub since_hit: f32,
+}
+
+impl Director {
+    pub fn accuracy(&self) -> f32 {
+        if self.shots > 0. {
+            self.hits / self.shots
+        } else {
+            0.
+        }
+    }
+
+    pub fn fire_scale(&self) -> f32 {
+        1. + self.adjustment
+    }
+
+    pub fn speed_scale(&self) -> f32 {
+        1. + self.adjustment / 2.
+    }
+
+    pub fn spawn_scale(&self) -> f32 {
+        1. + self.adjustment
+    }
+
+    pub fn spawn_cap(&self, level_count: u32) -> u32 {
+        ((level_count as f32 * self.spawn_scale()).round() as u32).max(1)
+    }
+}
+
+/// Resource - player preferences, persisted in `settings.ron`
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
     pub mute: bool,
+    pub volume: f32,
+    pub display_mode: DisplayMode,
+    pub difficulty: Difficulty,
+    pub adaptive: bool,
+    pub intercept: bool,
+}
+
+/// Resource - kills in a row without being hit, a step of the multiplier is lost
+/// whenever no kill happens for `COMBO_DECAY`
+#[derive(Resource)]
+pub struct Combo {
+    pub kills: u32,
+    pub timer: Timer,
+}
+
+impl Default for Combo {
+    fn default() -> Self {
+        Self {
+            kills: 0,
+            timer: Timer::new(consts::COMBO_DECAY, TimerMode::Repeating),
+        }
+    }
+}
+
+impl Combo {
+    pub fn kill(&mut self) {
+        self.kills += 1;
+        self.timer.reset();
+    }
+
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.kills / consts::COMBO_STEP).min(consts::COMBO_MAX_MULTIPLIER)
+    }
+
+    pub fn decay(&mut self, delta: Duration) {
+        if self.kills > 0 && self.timer.tick(delta).just_finished() {
+            self.kills =
+                (self.kills - self.kills % consts::COMBO_STEP).saturating_sub(consts::COMBO_STEP);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.kills = 0;
+        self.timer.reset();
+    }
+}
+
+/// Resource - what happened during the current run
+#[derive(Resource, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunStats {
+    pub shots_fired: u32,
+    pub hits: u32,
+    // indexed by the `GameLevel` reached with the kill
+    pub kills_per_level: [u32; GameLevel::ALL.len()],
+    pub deaths: u32,
+    pub time_survived: f32,
+    pub highest_fire_level: GameLevel,
+    pub longest_combo: u32,
+}
+
+impl RunStats {
+    /// Share of the lasers that hit an enemy, in percent
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.
+        } else {
+            self.hits as f32 * 100. / self.shots_fired as f32
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Achievement {
+    FirstKill,
+    ComboMaster,
+    NoHitLevel,
+    ReachInvincible,
+    HundredKillRun,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 5] = [
+        Achievement::FirstKill,
+        Achievement::ComboMaster,
+        Achievement::NoHitLevel,
+        Achievement::ReachInvincible,
+        Achievement::HundredKillRun,
+    ];
+}
+
+/// Resource - the player's career over every run, persisted in `career.ron`
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Career {
+    pub total_kills: u32,
+    pub runs_played: u32,
+    pub play_time: f32,
+    pub best_accuracy: f32,
+    pub achievements: Vec<Achievement>,
+}
+
+impl Career {
+    /// Returns true when the achievement was not unlocked yet
+    pub fn unlock(&mut self, achievement: Achievement) -> bool {
+        if self.achievements.contains(&achievement) {
+            return false;
+        }
+        self.achievements.push(achievement);
+        true
+    }
+}
+
+/// A finished run that made the top list
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub score: u32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub stats: RunStats,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+}
+
+/// Resource - the best runs of every difficulty, highest score first,
+/// persisted in `leaderboard.ron`
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(from = "LeaderboardFile")]
+pub struct Leaderboard {
+    pub tables: HashMap<Difficulty, Vec<LeaderboardEntry>>,
+}
+
+/// `leaderboard.ron` as read, files saved before the difficulties have a single `entries` list
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct LeaderboardFile {
+    tables: HashMap<Difficulty, Vec<LeaderboardEntry>>,
+    entries: Vec<LeaderboardEntry>,
+}
+
+impl From<LeaderboardFile> for Leaderboard {
+    fn from(file: LeaderboardFile) -> Self {
+        let mut tables = file.tables;
+        // the legacy entries have no difficulty, so they load as `Normal`
+        for entry in file.entries {
+            tables.entry(entry.difficulty).or_default().push(entry);
+        }
+        for entries in tables.values_mut() {
+            entries.sort_by_key(|entry| Reverse(entry.score));
+            entries.truncate(consts::HISTORY_LEN);
+        }
+        Self { tables }
+    }
+}
+
+impl Leaderboard {
+    pub fn entries(&self, difficulty: Difficulty) -> &[LeaderboardEntry] {
+        self.tables.get(&difficulty).map_or(&[], Vec::as_slice)
+    }
+
+    /// Insert the score when it makes the top list of its difficulty,
+    /// returns the rank achieved (1 is the best)
+    pub fn submit(
+        &mut self,
+        difficulty: Difficulty,
+        score: u32,
+        stats: &RunStats,
+    ) -> Option<usize> {
+        if score == 0 {
+            return None;
+        }
+
+        // ties rank below the older entry
+        let entries = self.tables.entry(difficulty).or_default();
+        let idx = entries
+            .iter()
+            .position(|entry| score > entry.score)
+            .unwrap_or(entries.len());
+        if idx >= consts::HISTORY_LEN {
+            return None;
+        }
+        entries.insert(
+            idx,
+            LeaderboardEntry {
+                score,
+                name: String::new(),
+                stats: stats.clone(),
+                difficulty,
+            },
+        );
+        entries.truncate(consts::HISTORY_LEN);
+        Some(idx + 1)
+    }
+
+    /// Sign the entry at `rank`
+    pub fn set_name(&mut self, difficulty: Difficulty, rank: usize, name: String) {
+        if let Some(entry) = rank
+            .checked_sub(1)
+            .and_then(|idx| self.tables.get_mut(&difficulty)?.get_mut(idx))
+        {
+            entry.name = name;
+        }
+    }
 }
 
 #[derive(Resource)]
@@ -56,6 +468,8 @@ pub struct PlayerState {
     pub invincible: bool,
     pub current_score: u32,
     pub total_score: u32,
+    // the score of the run, kills only count for the levels
+    pub points: u32,
     pub lives: u32,
 }
 
@@ -68,6 +482,7 @@ impl Default for PlayerState {
             invincible: true,
             current_score: 0,
             total_score: 0,
+            points: 0,
             lives: PLAYER_MAX_LIVES,
         }
     }
@@ -91,10 +506,11 @@ impl PlayerState {
         self.current_score = 0;
     }
 
-    pub fn replay(&mut self) {
-        self.lives = consts::PLAYER_MAX_LIVES;
+    pub fn replay(&mut self, difficulty: Difficulty) {
+        self.lives = difficulty.lives();
         self.current_score = 0;
         self.total_score = 0;
+        self.points = 0;
     }
 
     pub fn increase_score(&mut self) {
@@ -102,15 +518,14 @@ impl PlayerState {
         self.current_score += 1;
     }
 
-    pub fn hit_to_die(&mut self) -> bool {
-        if self.invincible {
-            if SystemTime::now()
+    pub fn hit_to_die(&mut self, invincible_duration: Duration) -> bool {
+        if self.invincible
+            && SystemTime::now()
                 .duration_since(self.born)
                 .unwrap()
-                .gt(&consts::PLAYER_INVINCIBLE_DURATION)
-            {
-                self.invincible = false;
-            }
+                .gt(&invincible_duration)
+        {
+            self.invincible = false;
         }
         !self.invincible
     }
@@ -123,14 +538,13 @@ impl PlayerState {
         Self::compute_game_level(self.total_score)
     }
 
-    fn compute_game_level(score: u32) -> GameLevel {
-        match score {
-            0..=9 => GameLevel::Basic,
-            10..=29 => GameLevel::Middle,
-            30..=59 => GameLevel::Strong,
-            60..=99 => GameLevel::Powerful,
-            _ => GameLevel::Invincible,
-        }
+    /// The level reached with `score` kills
+    pub fn compute_game_level(score: u32) -> GameLevel {
+        GameLevel::ALL
+            .into_iter()
+            .rev()
+            .find(|level| score >= level.min_kills())
+            .unwrap_or_default()
     }
 }
 
@@ -146,10 +560,12 @@ impl Default for EnemyState {
 }
 
 impl EnemyState {
+    /// Follows the game level, true when it went up
     pub fn update(&mut self, level: GameLevel) -> bool {
         if self.level == level {
             return false;
         }
+        let rose = level > self.level;
         match level {
             GameLevel::Basic => {
                 self.level = GameLevel::Basic;
@@ -177,28 +593,133 @@ impl EnemyState {
                 self.velocity = -1.3;
             }
         }
-        true
-    }
-}
-
-impl Default for GameState {
-    fn default() -> Self {
-        Self {
-            show_over: false,
-            is_over: false,
-        }
+        rose
     }
 }
 
 impl GameState {
-    pub fn reset(&mut self) {
+    pub fn reset(&mut self, difficulty: Difficulty) {
         self.show_over = false;
         self.is_over = false;
+        self.difficulty = difficulty;
+        // `god` carries over to the next run
+        self.cheated = self.god;
     }
 }
 
 impl Default for Settings {
     fn default() -> Self {
-        Self { mute: false }
+        Self {
+            mute: false,
+            volume: 1.,
+            display_mode: DisplayMode::Windowed,
+            difficulty: Difficulty::Normal,
+            adaptive: false,
+            intercept: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submit(leaderboard: &mut Leaderboard, score: u32) -> Option<usize> {
+        leaderboard.submit(Difficulty::Normal, score, &RunStats::default())
+    }
+
+    fn scores(leaderboard: &Leaderboard) -> Vec<u32> {
+        leaderboard
+            .entries(Difficulty::Normal)
+            .iter()
+            .map(|entry| entry.score)
+            .collect()
+    }
+
+    #[test]
+    fn submit_returns_the_rank() {
+        let mut leaderboard = Leaderboard::default();
+        assert_eq!(submit(&mut leaderboard, 50), Some(1));
+        assert_eq!(submit(&mut leaderboard, 80), Some(1));
+        assert_eq!(submit(&mut leaderboard, 60), Some(2));
+        assert_eq!(scores(&leaderboard), [80, 60, 50]);
+    }
+
+    #[test]
+    fn submit_ranks_a_tie_below_the_older_entry() {
+        let mut leaderboard = Leaderboard::default();
+        submit(&mut leaderboard, 50);
+        leaderboard.set_name(Difficulty::Normal, 1, "OLD".to_string());
+
+        assert_eq!(submit(&mut leaderboard, 50), Some(2));
+        assert_eq!(leaderboard.entries(Difficulty::Normal)[0].name, "OLD");
+    }
+
+    #[test]
+    fn submit_keeps_the_top_history_len() {
+        let mut leaderboard = Leaderboard::default();
+        for score in 1..=consts::HISTORY_LEN as u32 {
+            submit(&mut leaderboard, score * 10);
+        }
+
+        // lower than the whole full table, or tied with its last entry
+        assert_eq!(submit(&mut leaderboard, 5), None);
+        assert_eq!(submit(&mut leaderboard, 10), None);
+        assert_eq!(submit(&mut leaderboard, 0), None);
+
+        assert_eq!(submit(&mut leaderboard, 15), Some(consts::HISTORY_LEN));
+        assert_eq!(
+            leaderboard.entries(Difficulty::Normal).len(),
+            consts::HISTORY_LEN
+        );
+        assert_eq!(scores(&leaderboard).last(), Some(&15));
+    }
+
+    #[test]
+    fn submit_keeps_the_difficulties_apart() {
+        let mut leaderboard = Leaderboard::default();
+        submit(&mut leaderboard, 50);
+        assert_eq!(
+            leaderboard.submit(Difficulty::Hard, 10, &RunStats::default()),
+            Some(1)
+        );
+        assert_eq!(scores(&leaderboard), [50]);
+    }
+
+    #[test]
+    fn legacy_leaderboard_loads_into_normal() {
+        let legacy = "(entries: [(score: 40, name: \"AAA\"), (score: 70, name: \"BBB\")])";
+        let leaderboard: Leaderboard = ron::from_str(legacy).unwrap();
+
+        let scores: Vec<u32> = leaderboard
+            .entries(Difficulty::Normal)
+            .iter()
+            .map(|entry| entry.score)
+            .collect();
+        assert_eq!(scores, [70, 40]);
+        assert!(leaderboard.entries(Difficulty::Hard).is_empty());
+    }
+
+    #[test]
+    fn leaderboard_round_trips() {
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.submit(Difficulty::Hard, 90, &RunStats::default());
+
+        let saved = ron::to_string(&leaderboard).unwrap();
+        let loaded: Leaderboard = ron::from_str(&saved).unwrap();
+        assert_eq!(loaded.entries(Difficulty::Hard)[0].score, 90);
+    }
+
+    #[test]
+    fn enemy_state_only_reports_a_level_up() {
+        let mut enemy_state = EnemyState::default();
+        assert!(!enemy_state.update(GameLevel::Basic));
+        assert!(enemy_state.update(GameLevel::Strong));
+
+        // a replay drops the level back, which is no level up
+        assert!(!enemy_state.update(GameLevel::Basic));
+        assert_eq!(enemy_state.level, GameLevel::Basic);
+        assert_eq!(enemy_state.level_count, 2);
+        assert!(enemy_state.update(GameLevel::Middle));
     }
 }
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::EnemyKind,
+    entity::{Achievement, GameLevel},
+};
+
+pub struct GameEventsPlugin;
+
+impl Plugin for GameEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CollisionEvent>()
+            .add_event::<Recycle>()
+            .add_event::<EnemyKilled>()
+            .add_event::<PlayerHit>()
+            .add_event::<PlayerDied>()
+            .add_event::<LevelUp>()
+            .add_event::<GameOver>()
+            .add_event::<LaserFired>()
+            .add_event::<LaserIntercepted>()
+            .add_event::<ScoreGained>()
+            .add_event::<NewHighScore>()
+            .add_event::<AchievementUnlocked>()
+            .configure_sets(
+                (
+                    GameSet::Collide,
+                    GameSet::Emit,
+                    GameSet::React,
+                    GameSet::Present,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Frame order of gameplay: colliders are tested, systems emit events, state reacts, then HUD/audio/effects present.
+/// Listeners run outside `OnUpdate(InGame)` so no event is lost on a state change
+#[derive(SystemSet, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum GameSet {
+    Collide,
+    Emit,
+    React,
+    Present,
+}
+
+// region: --- Game Events
+
+/// Event - collider `.0` touched collider `.1`, whose layer is in the mask of `.0`
+pub struct CollisionEvent(pub Entity, pub Entity);
+
+/// Event - retire a pooled laser or explosion, anything else is despawned
+pub struct Recycle(pub Entity);
+
+/// Event - a player laser shot an enemy down, or the ship rammed it
+pub struct EnemyKilled {
+    pub kind: EnemyKind,
+    pub formation: u32,
+    pub position: Vec3,
+    pub rammed: bool,
+}
+
+/// Event - an enemy laser or ship struck the ship while it wasn't invincible
+pub struct PlayerHit {
+    pub player: Entity,
+    pub position: Vec3,
+}
+
+/// Event - the ship was destroyed
+pub struct PlayerDied {
+    pub position: Vec3,
+}
+
+/// Event - the enemies moved up to a new level
+pub struct LevelUp(pub GameLevel);
+
+/// Event - the last life was lost
+pub struct GameOver {
+    pub score: u32,
+}
+
+/// Event - a kill scored `points` (multiplier included) plus a formation `bonus`
+pub struct ScoreGained {
+    pub position: Vec3,
+    pub points: u32,
+    pub multiplier: u32,
+    pub bonus: u32,
+}
+
+/// Event - the player fired a volley of `count` lasers
+pub struct LaserFired {
+    pub level: GameLevel,
+    pub count: u32,
+}
+
+/// Event - a player laser shot down the enemy `laser`
+pub struct LaserIntercepted {
+    pub laser: Entity,
+    pub position: Vec3,
+}
+
+/// Event - the finished run entered the leaderboard at `rank`
+pub struct NewHighScore {
+    pub rank: usize,
+}
+
+/// Event - an achievement was unlocked for the first time
+pub struct AchievementUnlocked(pub Achievement);
+
+// endregion: --- Game Events
@@ -0,0 +1,118 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    consts::{COMMON_FONT_SIZE, MIDDLE_FONT_SIZE},
+    locale::{Language, Locale},
+    utils,
+};
+
+pub struct FontsPlugin;
+
+impl Plugin for FontsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(font_registry_system.in_base_set(StartupSet::PreStartup));
+    }
+}
+
+/// Named text styles shared by the HUD and menus
+#[derive(Clone, Copy)]
+pub enum FontStyle {
+    Hud,
+    HudLarge,
+    Title,
+    Prompt,
+}
+
+/// Fonts of one language, after falling back from missing files
+struct LanguageFonts {
+    regular: Handle<Font>,
+    title: Handle<Font>,
+    complete: bool,
+}
+
+/// Resource - validated font handles for every language
+#[derive(Resource)]
+pub struct FontRegistry {
+    fonts: HashMap<Language, LanguageFonts>,
+}
+
+impl FontRegistry {
+    pub fn font(&self, language: Language, style: FontStyle) -> Handle<Font> {
+        let fonts = &self.fonts[&language];
+        match style {
+            FontStyle::Hud | FontStyle::HudLarge => fonts.regular.clone(),
+            FontStyle::Title | FontStyle::Prompt => fonts.title.clone(),
+        }
+    }
+
+    pub fn style(&self, locale: &Locale, style: FontStyle, color: Color) -> TextStyle {
+        let font_size = match style {
+            FontStyle::Hud => COMMON_FONT_SIZE,
+            FontStyle::HudLarge => MIDDLE_FONT_SIZE,
+            FontStyle::Title => 32.,
+            FontStyle::Prompt => 22.,
+        };
+        TextStyle {
+            font: self.font(locale.language, style),
+            font_size,
+            color,
+        }
+    }
+
+    pub fn handles(&self) -> impl Iterator<Item = &Handle<Font>> {
+        self.fonts
+            .values()
+            .flat_map(|fonts| [&fonts.regular, &fonts.title])
+    }
+
+    /// Whether every font of the language shipped, the fallback may lack its glyphs
+    pub fn supports(&self, language: Language) -> bool {
+        self.fonts[&language].complete
+    }
+}
+
+fn font_registry_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut locale: ResMut<Locale>,
+) {
+    let mut fonts = HashMap::new();
+    for language in Language::ALL {
+        let strings = language.strings();
+        let mut complete = true;
+        let mut load = |path: &str| {
+            if utils::asset_exists(&asset_server, path) {
+                return asset_server.load(path);
+            }
+            complete = false;
+            error!(
+                "font `{}` is missing from the assets, falling back to `{}`",
+                path, strings.fallback_font
+            );
+            if !utils::asset_exists(&asset_server, strings.fallback_font) {
+                error!("fallback font `{}` is missing too", strings.fallback_font);
+            }
+            asset_server.load(strings.fallback_font)
+        };
+        let regular = load(strings.font);
+        let title = load(strings.title_font);
+        fonts.insert(
+            language,
+            LanguageFonts {
+                regular,
+                title,
+                complete,
+            },
+        );
+    }
+    let registry = FontRegistry { fonts };
+
+    // don't start in a language whose glyphs can't be drawn
+    if !registry.supports(locale.language) {
+        if let Some(language) = Language::ALL.into_iter().find(|l| registry.supports(*l)) {
+            warn!("switching language, the fonts of the default one are missing");
+            locale.language = language;
+        }
+    }
+    commands.insert_resource(registry);
+}
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+use crate::{
+    entity::{GameState, Leaderboard, RunStats},
+    events::{GameOver, GameSet, NewHighScore},
+    storage,
+};
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load::<Leaderboard>("leaderboard"))
+            // the run stats are final once the state reacted
+            .add_system(
+                leaderboard_submit_system
+                    .after(GameSet::React)
+                    .before(GameSet::Present),
+            );
+    }
+}
+
+fn leaderboard_submit_system(
+    mut leaderboard: ResMut<Leaderboard>,
+    run_stats: Res<RunStats>,
+    game_state: Res<GameState>,
+    mut game_over_events: EventReader<GameOver>,
+    mut new_high_score: EventWriter<NewHighScore>,
+) {
+    for game_over in game_over_events.iter() {
+        if game_state.cheated {
+            continue;
+        }
+        if let Some(rank) = leaderboard.submit(game_state.difficulty, game_over.score, &run_stats) {
+            storage::save("leaderboard", &*leaderboard);
+            new_high_score.send(NewHighScore { rank });
+        }
+    }
+}
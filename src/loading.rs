@@ -0,0 +1,196 @@
+use bevy::{asset::LoadState, prelude::*};
+
+use crate::{
+    components::{LoadingErrorText, LoadingProgressText, LoadingScreen, TextFonts},
+    consts,
+    entity::{AppState, GameAudio, GameTextures},
+    fonts::{FontRegistry, FontStyle},
+    locale::{fill, Locale},
+    utils,
+};
+
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(loading_start_system.in_schedule(OnEnter(AppState::Loading)))
+            .add_system(loading_progress_system.in_set(OnUpdate(AppState::Loading)))
+            .add_system(loading_despawn_system.in_schedule(OnExit(AppState::Loading)));
+    }
+}
+
+/// Resource - every handle the game waits for before it starts
+#[derive(Resource)]
+struct LoadingAssets(Vec<HandleUntyped>);
+
+fn loading_start_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    fonts: Res<FontRegistry>,
+    locale: Res<Locale>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    // create explosion texture altas
+    let explosion_sheet: Handle<Image> = asset_server.load(consts::EXPLOSION_SHEET);
+    let texture_atlas = TextureAtlas::from_grid(
+        explosion_sheet.clone(),
+        Vec2::new(64., 64.),
+        4,
+        4,
+        None,
+        None,
+    );
+
+    let game_textures = GameTextures {
+        player: asset_server.load(consts::PLAYER_SPRITE),
+        palyer_laser: asset_server.load(consts::PLAYER_LASER_SPRITE),
+        enemy: asset_server.load(consts::ENEMY_SPRITE),
+        enemy_laser: asset_server.load(consts::ENEMY_LASER_SPRITE),
+        explosion: texture_atlases.add(texture_atlas),
+    };
+    let game_audio = GameAudio {
+        explosion: asset_server.load(consts::AUDIOS_EXPLOSION),
+        level_upgrade: asset_server.load(consts::AUDIOS_LEVEL_UPGRADE),
+        player_fail: asset_server.load(consts::AUDIOS_PLAYER_FAIL),
+        shot_low: asset_server.load(consts::AUDIOS_SHOT_LOW),
+        shot_mid: asset_server.load(consts::AUDIOS_SHOT_MID),
+        shot_high: asset_server.load(consts::AUDIOS_SHOT_HIGH),
+        music_calm: asset_server.load(consts::MUSIC_CALM),
+        music_battle: asset_server.load(consts::MUSIC_BATTLE),
+        music_boss: asset_server.load(consts::MUSIC_BOSS),
+        music_game_over: asset_server.load(consts::MUSIC_GAME_OVER),
+        voice_on: asset_server.load(consts::ICONS_VOICE_ON),
+        voice_off: asset_server.load(consts::ICONS_VOICE_OFF),
+    };
+
+    let mut handles = vec![
+        game_textures.player.clone_untyped(),
+        game_textures.palyer_laser.clone_untyped(),
+        game_textures.enemy.clone_untyped(),
+        game_textures.enemy_laser.clone_untyped(),
+        explosion_sheet.clone_untyped(),
+        game_audio.explosion.clone_untyped(),
+        game_audio.level_upgrade.clone_untyped(),
+        game_audio.player_fail.clone_untyped(),
+        game_audio.shot_low.clone_untyped(),
+        game_audio.shot_mid.clone_untyped(),
+        game_audio.shot_high.clone_untyped(),
+        game_audio.music_calm.clone_untyped(),
+        game_audio.music_battle.clone_untyped(),
+        game_audio.music_boss.clone_untyped(),
+        game_audio.music_game_over.clone_untyped(),
+        game_audio.voice_on.clone_untyped(),
+        game_audio.voice_off.clone_untyped(),
+    ];
+    for font in fonts.handles() {
+        if !handles.iter().any(|handle| handle.id() == font.id()) {
+            handles.push(font.clone_untyped());
+        }
+    }
+    commands.insert_resource(LoadingAssets(handles));
+    commands.insert_resource(game_textures);
+    commands.insert_resource(game_audio);
+
+    // loading screen
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|builder| {
+            builder
+                .spawn(TextBundle::from_sections([TextSection::new(
+                    fill(locale.strings().loading, ""),
+                    fonts.style(&locale, FontStyle::Title, Color::WHITE),
+                )]))
+                .insert(TextFonts(vec![FontStyle::Title]))
+                .insert(LoadingProgressText);
+            builder
+                .spawn(TextBundle::from_sections([TextSection::new(
+                    "",
+                    fonts.style(&locale, FontStyle::Hud, Color::ORANGE_RED),
+                )]))
+                .insert(TextFonts(vec![FontStyle::Hud]))
+                .insert(LoadingErrorText);
+        })
+        .insert(LoadingScreen);
+}
+
+fn loading_progress_system(
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    loading: Res<LoadingAssets>,
+    kb: Res<Input<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut reported: Local<bool>,
+    mut text_set: ParamSet<(
+        Query<&mut Text, With<LoadingProgressText>>,
+        Query<&mut Text, With<LoadingErrorText>>,
+    )>,
+) {
+    let mut loaded = 0;
+    let mut failed = Vec::new();
+    for handle in &loading.0 {
+        match asset_server.get_load_state(handle) {
+            LoadState::Loaded => loaded += 1,
+            LoadState::Failed => failed.push(handle),
+            _ => {}
+        }
+    }
+
+    let total = loading.0.len();
+    let progress = format!("{}/{}", loaded, total);
+    for mut text in &mut text_set.p0() {
+        text.sections[0].value = fill(locale.strings().loading, &progress);
+    }
+
+    if loaded == total {
+        next_state.set(AppState::InGame);
+        return;
+    }
+    if loaded + failed.len() < total {
+        return;
+    }
+
+    // every asset settled but some failed, name them and let the player decide
+    let strings = locale.strings();
+    let mut txt = strings.loading_failed.to_string();
+    for handle in failed {
+        let path = asset_server
+            .get_handle_path(handle)
+            .map(|path| path.path().display().to_string())
+            .unwrap_or_default();
+        let reason = if utils::asset_exists(&asset_server, &path) {
+            strings.loading_broken
+        } else {
+            strings.loading_missing
+        };
+        if !*reported {
+            error!("asset `{}` failed to load ({})", path, reason);
+        }
+        txt.push_str(format!("\n{} ({})", path, reason).as_str());
+    }
+    txt.push_str(format!("\n\n{}", strings.loading_continue).as_str());
+    *reported = true;
+    for mut text in &mut text_set.p1() {
+        text.sections[0].value = txt.clone();
+    }
+
+    if kb.just_pressed(KeyCode::Return) {
+        next_state.set(AppState::InGame);
+    }
+}
+
+fn loading_despawn_system(mut commands: Commands, query: Query<Entity, With<LoadingScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<LoadingAssets>();
+}
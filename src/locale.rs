@@ -0,0 +1,280 @@
+use std::fmt::Display;
+
+use bevy::prelude::*;
+
+use crate::{
+    consts,
+    entity::{AppState, Difficulty},
+    fonts::FontRegistry,
+};
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Locale::default())
+            // letter keys type the name or the console command while it is entered
+            .add_system(
+                locale_switch_system
+                    .run_if(not(in_state(AppState::NameEntry)))
+                    .run_if(not(in_state(AppState::Console))),
+            );
+    }
+}
+
+/// Languages the game text is available in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Language {
+    #[default]
+    Chinese,
+    English,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Chinese, Language::English];
+
+    pub fn next(self) -> Self {
+        match self {
+            Language::Chinese => Language::English,
+            Language::English => Language::Chinese,
+        }
+    }
+
+    pub fn strings(self) -> &'static Strings {
+        match self {
+            Language::Chinese => &ZH,
+            Language::English => &EN,
+        }
+    }
+}
+
+/// String table of one language, `{}` is replaced by [`fill`]
+pub struct Strings {
+    pub window_title: &'static str,
+    pub score: &'static str,
+    pub combo: &'static str,
+    pub formation_bonus: &'static str,
+    pub current_score: &'static str,
+    pub total_score: &'static str,
+    pub history: &'static str,
+    pub new_high_score: &'static str,
+    pub achievement_unlocked: &'static str,
+    pub achievements_title: &'static str,
+    pub achievements_back: &'static str,
+    pub career_kills: &'static str,
+    pub career_runs: &'static str,
+    pub career_time: &'static str,
+    pub career_accuracy: &'static str,
+    pub achievement_first_kill: &'static str,
+    pub achievement_combo_master: &'static str,
+    pub achievement_no_hit_level: &'static str,
+    pub achievement_reach_invincible: &'static str,
+    pub achievement_hundred_kill_run: &'static str,
+    pub name_entry_title: &'static str,
+    pub name_entry_prompt: &'static str,
+    pub lives: &'static str,
+    pub game_over: &'static str,
+    pub game_over_prompt: &'static str,
+    pub stats_shots: &'static str,
+    pub stats_accuracy: &'static str,
+    pub stats_kills_per_level: &'static str,
+    pub stats_deaths: &'static str,
+    pub stats_time: &'static str,
+    pub stats_fire_level: &'static str,
+    pub stats_combo: &'static str,
+    pub loading: &'static str,
+    pub loading_failed: &'static str,
+    pub loading_missing: &'static str,
+    pub loading_broken: &'static str,
+    pub loading_continue: &'static str,
+    pub language_name: &'static str,
+    pub settings_title: &'static str,
+    pub settings_sound: &'static str,
+    pub settings_volume: &'static str,
+    pub settings_display: &'static str,
+    pub settings_language: &'static str,
+    pub settings_difficulty: &'static str,
+    pub settings_next_run: &'static str,
+    pub settings_adaptive: &'static str,
+    pub settings_intercept: &'static str,
+    pub easy: &'static str,
+    pub normal: &'static str,
+    pub hard: &'static str,
+    pub nightmare: &'static str,
+    pub settings_back: &'static str,
+    pub on: &'static str,
+    pub off: &'static str,
+    pub windowed: &'static str,
+    pub fullscreen: &'static str,
+    // fonts able to render this language, fallback used when they are missing
+    pub font: &'static str,
+    pub title_font: &'static str,
+    pub fallback_font: &'static str,
+}
+
+impl Strings {
+    pub fn difficulty(&self, difficulty: Difficulty) -> &'static str {
+        match difficulty {
+            Difficulty::Easy => self.easy,
+            Difficulty::Normal => self.normal,
+            Difficulty::Hard => self.hard,
+            Difficulty::Nightmare => self.nightmare,
+        }
+    }
+}
+
+const ZH: Strings = Strings {
+    window_title: "侵入者！",
+    score: "得分: {}",
+    combo: "连击 x{}",
+    formation_bonus: "全歼 +{}",
+    current_score: "当前歼灭: {}",
+    total_score: "总共歼灭: {}",
+    history: "历史记录",
+    new_high_score: "新纪录！第 {} 名",
+    achievement_unlocked: "成就解锁: {}",
+    achievements_title: "成就",
+    achievements_back: "按[H]返回",
+    career_kills: "总歼灭: {}",
+    career_runs: "游戏局数: {}",
+    career_time: "游戏时间: {}",
+    career_accuracy: "最佳命中率: {}",
+    achievement_first_kill: "初次击落 - 击落第一架敌机",
+    achievement_combo_master: "连击大师 - 达到最高连击倍数",
+    achievement_no_hit_level: "毫发无伤 - 一个等级内未被击中",
+    achievement_reach_invincible: "所向披靡 - 到达无敌等级",
+    achievement_hundred_kill_run: "百人斩 - 一局歼灭 100 架敌机",
+    name_entry_title: "输入你的名字",
+    name_entry_prompt: "[上/下] 选择字母  [左/右] 移动\n[Enter] 确认",
+    lives: "生命值: {}",
+    game_over: "游戏结束",
+    game_over_prompt: "按[P]继续\n按[Esc]退出",
+    stats_shots: "射击/命中: {}",
+    stats_accuracy: "命中率: {}",
+    stats_kills_per_level: "各级歼灭: {}",
+    stats_deaths: "阵亡: {}",
+    stats_time: "存活时间: {}",
+    stats_fire_level: "最高火力: {}",
+    stats_combo: "最长连击: {}",
+    loading: "加载中 {}",
+    loading_failed: "以下资源加载失败:",
+    loading_missing: "缺失",
+    loading_broken: "无法解析",
+    loading_continue: "按[Enter]继续",
+    language_name: "中文",
+    settings_title: "设置",
+    settings_sound: "[M] 声音: {}",
+    settings_volume: "[-/+] 音量: {}",
+    settings_display: "[F11] 显示: {}",
+    settings_language: "[L] 语言: {}",
+    settings_difficulty: "[左/右] 难度: {}",
+    settings_next_run: "(下一局生效)",
+    settings_adaptive: "[D] 动态难度: {}",
+    settings_intercept: "[I] 击落敌方子弹: {}",
+    easy: "简单",
+    normal: "普通",
+    hard: "困难",
+    nightmare: "噩梦",
+    settings_back: "按[Tab]返回",
+    on: "开",
+    off: "关",
+    windowed: "窗口",
+    fullscreen: "全屏",
+    font: consts::FONT_CJK_LIGHT,
+    title_font: consts::FONT_CJK_MEDIUM,
+    fallback_font: consts::FONT_LATIN,
+};
+
+const EN: Strings = Strings {
+    window_title: "Invaders!",
+    score: "Score: {}",
+    combo: "Combo x{}",
+    formation_bonus: "Wipeout +{}",
+    current_score: "Kills: {}",
+    total_score: "Total kills: {}",
+    history: "High Scores",
+    new_high_score: "New high score! #{}",
+    achievement_unlocked: "Achievement unlocked: {}",
+    achievements_title: "Achievements",
+    achievements_back: "Press [H] to go back",
+    career_kills: "Total kills: {}",
+    career_runs: "Runs played: {}",
+    career_time: "Play time: {}",
+    career_accuracy: "Best accuracy: {}",
+    achievement_first_kill: "First Blood - shoot down an enemy",
+    achievement_combo_master: "Combo Master - reach the highest multiplier",
+    achievement_no_hit_level: "Untouchable - clear a level without being hit",
+    achievement_reach_invincible: "Unstoppable - reach the Invincible level",
+    achievement_hundred_kill_run: "Centurion - 100 kills in one run",
+    name_entry_title: "Enter your name",
+    name_entry_prompt: "[Up/Down] letter  [Left/Right] move\n[Enter] confirm",
+    lives: "Lives: {}",
+    game_over: "Game Over",
+    game_over_prompt: "Press [P] to continue\nPress [Esc] to quit",
+    stats_shots: "Shots / hits: {}",
+    stats_accuracy: "Accuracy: {}",
+    stats_kills_per_level: "Kills per level: {}",
+    stats_deaths: "Deaths: {}",
+    stats_time: "Time survived: {}",
+    stats_fire_level: "Highest fire level: {}",
+    stats_combo: "Longest combo: {}",
+    loading: "Loading {}",
+    loading_failed: "These assets failed to load:",
+    loading_missing: "missing",
+    loading_broken: "unreadable",
+    loading_continue: "Press [Enter] to continue",
+    language_name: "English",
+    settings_title: "Settings",
+    settings_sound: "[M] Sound: {}",
+    settings_volume: "[-/+] Volume: {}",
+    settings_display: "[F11] Display: {}",
+    settings_language: "[L] Language: {}",
+    settings_difficulty: "[Left/Right] Difficulty: {}",
+    settings_next_run: "(from the next run)",
+    settings_adaptive: "[D] Adaptive difficulty: {}",
+    settings_intercept: "[I] Shoot down enemy lasers: {}",
+    easy: "easy",
+    normal: "normal",
+    hard: "hard",
+    nightmare: "nightmare",
+    settings_back: "Press [Tab] to go back",
+    on: "on",
+    off: "off",
+    windowed: "windowed",
+    fullscreen: "fullscreen",
+    font: consts::FONT_LATIN,
+    title_font: consts::FONT_LATIN,
+    fallback_font: consts::FONT_LATIN,
+};
+
+/// Resource - the active language
+#[derive(Default, Resource)]
+pub struct Locale {
+    pub language: Language,
+}
+
+impl Locale {
+    pub fn strings(&self) -> &'static Strings {
+        self.language.strings()
+    }
+}
+
+/// Fill the `{}` placeholder of a string table entry
+pub fn fill(template: &str, value: impl Display) -> String {
+    template.replacen("{}", &value.to_string(), 1)
+}
+
+fn locale_switch_system(
+    kb: Res<Input<KeyCode>>,
+    fonts: Res<FontRegistry>,
+    mut locale: ResMut<Locale>,
+) {
+    if kb.just_pressed(KeyCode::L) {
+        let language = locale.language.next();
+        if fonts.supports(language) {
+            locale.language = language;
+        } else {
+            warn!("can't switch to {:?}, its fonts are missing", language);
+        }
+    }
+}
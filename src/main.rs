@@ -1,38 +1,84 @@
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
 use std::collections::HashSet;
 
-use bevy::{
-    app::AppExit,
-    math::Vec3Swizzles,
-    prelude::*,
-    sprite::collide_aabb::collide,
-    window::{PrimaryWindow, WindowResized, WindowResolution},
-};
+use achievements::AchievementsPlugin;
+use audio_play::{AudioPlayPlugin, PlaySfx};
+use bevy::{app::AppExit, math::Vec3Swizzles, prelude::*, window::WindowResolution};
 use bevy_embedded_assets::EmbeddedAssetPlugin;
+use collision::CollisionPlugin;
 use components::{
-    CurrentScoreText, Enemy, Explosion, ExplosionTimer, ExplosionToSpawn, FromEnemy, FromPlayer,
-    GameOverText, HistoryScoreText, Laser, LifeText, Movable, Player, SpriteSize, TotalScoreText,
-    Velocity,
+    Bounded, Enemy, EnemyKind, Explosion, ExplosionTimer, ExplosionToSpawn, FromEnemy, FromPlayer,
+    GameOverText, Interceptible, Laser, Movable, Player, PlayfieldRoot, PoolKind, Pooled, Spark,
+    SpriteSize, Velocity,
+};
+use console::ConsolePlugin;
+use debug::DebugPlugin;
+use director::DirectorPlugin;
+use enemy::{EnemyPlugin, Formation, FormationMaker};
+use entity::{
+    AppState, Director, EnemyState, GameLevel, GameState, GameTextures, PlayArea, PlayerState,
+    RunStats, Settings, WinSize,
+};
+use events::{
+    CollisionEvent, EnemyKilled, GameEventsPlugin, GameSet, LaserIntercepted, PlayerDied,
+    PlayerHit, Recycle,
 };
-use enemy::EnemyPlugin;
-use entity::{EnemyState, GameState, GameTextures, PlayerState, Settings, WinSize};
+use fonts::{FontRegistry, FontsPlugin};
+use leaderboard::LeaderboardPlugin;
+use loading::LoadingPlugin;
+use locale::{Locale, LocalePlugin};
+use music::MusicPlugin;
+use name_entry::NameEntryPlugin;
 use player::PlayerPlugin;
+use playfield::PlayfieldPlugin;
+use pool::{PoolPlugin, Pools};
+use score::ScorePlugin;
 use settings::SettingsPlugin;
+use stats::StatsPlugin;
 use text::TextPlugin;
 
+mod achievements;
 mod audio_play;
+mod broadphase;
+mod collision;
 mod components;
+mod console;
 mod consts;
+mod debug;
+mod director;
 mod enemy;
 mod entity;
+mod events;
+mod fonts;
+mod leaderboard;
+mod loading;
+mod locale;
+mod music;
+mod name_entry;
 mod player;
+mod playfield;
+mod pool;
+mod score;
 mod settings;
+mod stats;
+mod storage;
 mod text;
 mod utils;
 
-#[macro_use]
-extern crate lazy_static;
-
 fn main() {
+    if std::env::args().any(|arg| arg == "--bench-collisions") {
+        broadphase::bench();
+        return;
+    }
+    if std::env::args().any(|arg| arg == "--bench-pool") {
+        #[cfg(feature = "bench-pool")]
+        pool::bench();
+        #[cfg(not(feature = "bench-pool"))]
+        eprintln!("--bench-pool counts allocations, build it with `--features bench-pool`");
+        return;
+    }
+
     App::new()
         .insert_resource(ClearColor(Color::rgba(0.29, 0.39, 0.46, 0.85)))
         .add_plugins(
@@ -42,90 +88,99 @@ fn main() {
                 .add_before::<bevy::asset::AssetPlugin, _>(EmbeddedAssetPlugin)
                 .set(WindowPlugin {
                     primary_window: Some(Window {
-                        title: "侵入者！".to_string(),
-                        resolution: WindowResolution::new(598.0, 676.0),
-                        resizable: false,
+                        title: Locale::default().strings().window_title.to_string(),
+                        resolution: WindowResolution::new(
+                            consts::PLAYFIELD_SIZE.0,
+                            consts::PLAYFIELD_SIZE.1,
+                        ),
+                        resizable: true,
                         ..Default::default()
                     }),
                     ..Default::default()
                 }),
         )
-        // .add_system(window_resize_listener) // FIXME, this will be exe every tick time
+        // before the plugins, their OnEnter/OnExit schedules come with the state
+        .add_state::<AppState>()
+        .add_plugin(PlayfieldPlugin)
+        .add_plugin(LocalePlugin)
+        .add_plugin(FontsPlugin)
         .add_plugin(SettingsPlugin)
         .add_plugin(PlayerPlugin)
         .add_plugin(EnemyPlugin)
         .add_plugin(TextPlugin)
+        .add_plugin(GameEventsPlugin)
+        .add_plugin(ScorePlugin)
+        .add_plugin(StatsPlugin)
+        .add_plugin(AchievementsPlugin)
+        .add_plugin(DirectorPlugin)
+        .add_plugin(CollisionPlugin)
+        .add_plugin(PoolPlugin)
+        .add_plugin(DebugPlugin)
+        .add_plugin(ConsolePlugin)
+        .add_plugin(LeaderboardPlugin)
+        .add_plugin(NameEntryPlugin)
+        .add_plugin(AudioPlayPlugin)
+        .add_plugin(MusicPlugin)
+        .add_plugin(LoadingPlugin)
         .add_startup_system(setup_system)
-        .add_system(movable_system)
-        .add_system(player_laser_hit_enemy_system)
-        .add_system(enemy_laser_hit_player_system)
-        .add_system(explosion_to_spawn_system)
-        .add_system(explosion_animation_system)
-        .add_system(game_over_system)
+        .add_systems(
+            (
+                movable_system.before(GameSet::Collide),
+                bounds_system.after(movable_system).before(GameSet::Collide),
+                enemy_collision_system.in_set(GameSet::Emit),
+                player_collision_system
+                    .in_set(GameSet::Emit)
+                    .after(enemy_collision_system),
+                explosion_to_spawn_system,
+                explosion_animation_system,
+                spark_system,
+                game_over_system.after(GameSet::React),
+            )
+                .in_set(OnUpdate(AppState::InGame)),
+        )
+        .add_systems((explosion_event_system, spark_event_system).in_set(GameSet::Present))
         .run();
 }
 
 fn setup_system(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    mut primary_query: Query<&mut Window, With<PrimaryWindow>>,
+    settings: Res<Settings>,
+    mut player_state: ResMut<PlayerState>,
 ) {
-    // camera
-    commands.spawn(Camera2dBundle::default());
-
-    // capture window size
-    let window = primary_query.get_single_mut().unwrap();
-    let (win_w, win_h) = (window.width(), window.height());
-
-    // add WinSize resource
-    let win_size = WinSize { w: win_w, h: win_h };
+    // add WinSize resource, the logical playfield the camera scales to the window
+    let win_size = WinSize {
+        w: consts::PLAYFIELD_SIZE.0,
+        h: consts::PLAYFIELD_SIZE.1,
+    };
+    commands.insert_resource(PlayArea::from(&win_size));
     commands.insert_resource(win_size);
 
-    // create explosion texture altas
-    let texture_handle = asset_server.load(consts::EXPLOSION_SHEET);
-    let texture_atlas =
-        TextureAtlas::from_grid(texture_handle, Vec2::new(64., 64.), 4, 4, None, None);
-    let explosion = texture_atlases.add(texture_atlas);
-
-    // add GameTextures resource
-    let game_textures = GameTextures {
-        player: asset_server.load(consts::PLAYER_SPRITE),
-        palyer_laser: asset_server.load(consts::PLAYER_LASER_SPRITE),
-        enemy: asset_server.load(consts::ENEMY_SPRITE),
-        enemy_laser: asset_server.load(consts::ENEMY_LASER_SPRITE),
-        explosion,
-    };
-    commands.insert_resource(game_textures);
+    // textures and audios are loaded by the LoadingPlugin
     commands.insert_resource(EnemyState::default());
 
-    // add game state resource
-    commands.insert_resource(GameState::default());
-
-    // settings resource
-    commands.insert_resource(Settings::default());
-}
-
-#[allow(dead_code)]
-fn window_resize_listener(
-    mut win_size: ResMut<WinSize>,
-    mut resize_events: EventReader<WindowResized>,
-) {
-    for e in resize_events.iter() {
-        win_size.w = e.width;
-        win_size.h = e.height;
-    }
+    // add game state resource, the first run uses the saved difficulty
+    let mut game_state = GameState::default();
+    game_state.reset(settings.difficulty);
+    player_state.replay(settings.difficulty);
+    commands.insert_resource(game_state);
 }
 
 fn movable_system(
-    mut commands: Commands,
+    time: Res<Time>,
     win_size: Res<WinSize>,
-    mut query: Query<(Entity, &Velocity, &mut Transform, &Movable)>,
+    mut recycle: EventWriter<Recycle>,
+    mut query: Query<(Entity, &Velocity, &mut Transform, &Movable, Option<&Pooled>)>,
 ) {
-    for (entity, velocity, mut transform, movable) in query.iter_mut() {
+    // dev console `timescale` slows the fixed step down too
+    let step = consts::TIME_STEP * time.relative_speed();
+    for (entity, velocity, mut transform, movable, pooled) in query.iter_mut() {
+        if pooled.is_some_and(|pooled| !pooled.active) {
+            continue;
+        }
+
         let translation = &mut transform.translation;
-        translation.x += velocity.x * consts::TIME_STEP * consts::BASE_SPEED;
-        translation.y += velocity.y * consts::TIME_STEP * consts::BASE_SPEED;
+        translation.x += velocity.x * step * consts::BASE_SPEED;
+        translation.y += velocity.y * step * consts::BASE_SPEED;
 
         if movable.auto_despawn {
             const MARGIN: f32 = 200.;
@@ -134,172 +189,263 @@ fn movable_system(
                 || translation.x > win_size.w / 2. + MARGIN
                 || translation.x < -win_size.w / 2. - MARGIN
             {
-                commands.entity(entity).despawn();
+                recycle.send(Recycle(entity));
             }
         }
     }
 }
 
-fn player_laser_hit_enemy_system(
+fn bounds_system(
+    play_area: Res<PlayArea>,
+    mut query: Query<(&mut Transform, &SpriteSize, &Bounded)>,
+) {
+    for (mut transform, size, bounded) in query.iter_mut() {
+        let area = bounded.area.unwrap_or(play_area.0);
+        let half_size = size.0 * transform.scale.xy() / 2.;
+        let (min, max) = (area.min + half_size, area.max - half_size);
+
+        // an area smaller than the sprite pins it to the center on that axis
+        let center = area.center();
+        let translation = &mut transform.translation;
+        translation.x = if min.x <= max.x {
+            translation.x.clamp(min.x, max.x)
+        } else {
+            center.x
+        };
+        translation.y = if min.y <= max.y {
+            translation.y.clamp(min.y, max.y)
+        } else {
+            center.y
+        };
+    }
+}
+
+/// Enemies are shot down by player lasers or rammed by the ship,
+/// the ship's side of a ram is left to `player_collision_system`.
+/// Player lasers also shoot down interceptible enemy lasers
+fn enemy_collision_system(
     mut commands: Commands,
-    settings: Res<Settings>,
-    asset_server: Res<AssetServer>,
-    audio: Res<Audio>,
-    mut enemy_state: ResMut<EnemyState>,
-    mut player_state: ResMut<PlayerState>,
-    laser_query: Query<(Entity, &Transform, &SpriteSize), (With<Laser>, With<FromPlayer>)>,
-    enemy_query: Query<(Entity, &Transform, &SpriteSize), With<Enemy>>,
-    mut text_set: ParamSet<(
-        Query<&mut Text, With<CurrentScoreText>>,
-        Query<&mut Text, With<TotalScoreText>>,
-    )>,
+    mut collisions: EventReader<CollisionEvent>,
+    mut recycle: EventWriter<Recycle>,
+    mut killed: EventWriter<EnemyKilled>,
+    mut intercepted: EventWriter<LaserIntercepted>,
+    laser_query: Query<(), (With<Laser>, With<FromPlayer>)>,
+    player_query: Query<(), With<Player>>,
+    enemy_query: Query<(&Transform, &EnemyKind, &Formation), With<Enemy>>,
+    interceptible_query: Query<(&Transform, &Interceptible)>,
 ) {
     let mut despawned_entities: HashSet<Entity> = HashSet::new();
 
-    // iterate through the laser
-    for (laser_entity, laser_tf, laser_size) in laser_query.iter() {
-        if despawned_entities.contains(&laser_entity) {
+    for CollisionEvent(hitter, enemy_entity) in collisions.iter() {
+        if despawned_entities.contains(hitter) || despawned_entities.contains(enemy_entity) {
             continue;
         }
 
-        let laser_scale = Vec2::from(laser_tf.scale.xy());
-
-        // iterate through the enemies;
-        for (enemy_enity, enemy_tf, enemy_size) in enemy_query.iter() {
-            if despawned_entities.contains(&enemy_enity)
-                || despawned_entities.contains(&laser_entity)
-            {
+        if let Ok((enemy_tf, kind, formation)) = enemy_query.get(*enemy_entity) {
+            let rammed = player_query.contains(*hitter);
+            if laser_query.contains(*hitter) {
+                // remove the laser
+                recycle.send(Recycle(*hitter));
+                despawned_entities.insert(*hitter);
+            } else if !rammed {
                 continue;
             }
 
-            let enemy_scale = Vec2::from(enemy_tf.scale.xy());
+            // remove the enemy
+            commands.entity(*enemy_entity).despawn();
+            despawned_entities.insert(*enemy_entity);
+
+            // score, enemy state, explosion and HUD react to the event
+            killed.send(EnemyKilled {
+                kind: *kind,
+                formation: formation.id,
+                position: enemy_tf.translation,
+                rammed,
+            });
+        } else if let Ok((enemy_laser_tf, interceptible)) = interceptible_query.get(*enemy_entity) {
+            if !interceptible.0 || !laser_query.contains(*hitter) {
+                continue;
+            }
 
-            // determine if collision
-            let collision = collide(
-                laser_tf.translation,
-                laser_size.0 * laser_scale,
-                enemy_tf.translation,
-                enemy_size.0 * enemy_scale,
-            );
+            // remove both lasers
+            recycle.send(Recycle(*hitter));
+            despawned_entities.insert(*hitter);
+            recycle.send(Recycle(*enemy_entity));
+            despawned_entities.insert(*enemy_entity);
 
-            // perform collision
-            if let Some(_) = collision {
-                // remove the enemy
-                commands.entity(enemy_enity).despawn();
-                despawned_entities.insert(enemy_enity);
-                enemy_state.count -= 1;
+            intercepted.send(LaserIntercepted {
+                laser: *enemy_entity,
+                position: enemy_laser_tf.translation,
+            });
+        }
+    }
+}
 
-                // remove the laser
-                commands.entity(laser_entity).despawn();
-                despawned_entities.insert(laser_entity);
+/// The ship is hurt by enemy lasers and by ramming enemies, at most once a frame.
+/// An enemy laser shot down this frame no longer hurts
+fn player_collision_system(
+    mut player_state: ResMut<PlayerState>,
+    game_state: Res<GameState>,
+    mut collisions: EventReader<CollisionEvent>,
+    mut intercepted_events: EventReader<LaserIntercepted>,
+    mut recycle: EventWriter<Recycle>,
+    mut hit: EventWriter<PlayerHit>,
+    laser_query: Query<(), (With<Laser>, With<FromEnemy>)>,
+    enemy_query: Query<(), With<Enemy>>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let intercepted: HashSet<Entity> = intercepted_events.iter().map(|e| e.laser).collect();
 
-                // spawn the ExplosionToSpawn
-                commands.spawn(ExplosionToSpawn(enemy_tf.translation.clone()));
+    // every event is read, a leftover would name a laser the pool may hand out next frame
+    let mut hit_this_frame = false;
+    for CollisionEvent(a, b) in collisions.iter() {
+        if hit_this_frame {
+            continue;
+        }
 
-                // update the score
-                player_state.increase_score();
+        let (player_entity, laser_entity) = if laser_query.contains(*a) {
+            if intercepted.contains(a) {
+                continue;
+            }
+            (b, Some(a))
+        } else if enemy_query.contains(*b) {
+            (a, None)
+        } else {
+            continue;
+        };
 
-                // udpate enemy state
-                if enemy_state.update(player_state.get_game_level()) {
-                    // play leve upgrade music
-                    audio_play::leve_upgrade(&settings, &asset_server, &audio);
-                }
+        if let Ok(player_tf) = player_query.get(*player_entity) {
+            if game_state.god
+                || !player_state.hit_to_die(game_state.difficulty.invincible_duration())
+            {
+                continue;
+            }
 
-                // update score text
-                CurrentScoreText::update(text_set.p0(), player_state.current_score);
-                TotalScoreText::update(text_set.p1(), player_state.total_score);
+            // remove the laser, a rammed enemy is removed by `enemy_collision_system`
+            if let Some(laser_entity) = laser_entity {
+                recycle.send(Recycle(*laser_entity));
             }
+
+            // lives, explosion and HUD react to the event
+            hit.send(PlayerHit {
+                player: *player_entity,
+                position: player_tf.translation,
+            });
+            hit_this_frame = true;
         }
     }
 }
 
-fn enemy_laser_hit_player_system(
+fn explosion_event_system(
     mut commands: Commands,
-    mut player_state: ResMut<PlayerState>,
-    mut game_state: ResMut<GameState>,
-    time: Res<Time>,
-    laser_query: Query<(Entity, &Transform, &SpriteSize), (With<Laser>, With<FromEnemy>)>,
-    player_query: Query<(Entity, &Transform, &SpriteSize), With<Player>>,
-    text_query: Query<&mut Text, With<LifeText>>,
+    mut killed_events: EventReader<EnemyKilled>,
+    mut died_events: EventReader<PlayerDied>,
 ) {
-    if let Ok((player_entity, player_tf, player_size)) = player_query.get_single() {
-        let player_scale = player_tf.scale.xy();
-
-        for (laser_entity, laser_tf, laser_size) in laser_query.iter() {
-            let laser_scale = laser_tf.scale.xy();
-
-            let collision = collide(
-                laser_tf.translation,
-                laser_size.0 * laser_scale,
-                player_tf.translation,
-                player_size.0 * player_scale,
-            );
-
-            // perform the collision
-            if let Some(_) = collision {
-                if !player_state.hit_to_die() {
-                    break;
-                }
-
-                // remove the player
-                commands.entity(player_entity).despawn();
-                if player_state.shot(time.elapsed_seconds_f64()) == 0 {
-                    game_state.is_over = true;
-                }
-
-                // remove the laser
-                commands.entity(laser_entity).despawn();
-
-                // spawn the ExplosionToSpawn
-                commands.spawn(ExplosionToSpawn(player_tf.translation.clone()));
+    let enemies = killed_events.iter().map(|e| e.position);
+    let players = died_events.iter().map(|e| e.position);
+    for position in enemies.chain(players) {
+        commands.spawn(ExplosionToSpawn(position));
+    }
+}
 
-                // update life text
-                LifeText::update(text_query, player_state.lives);
+fn spark_event_system(
+    mut commands: Commands,
+    mut intercepted_events: EventReader<LaserIntercepted>,
+) {
+    for intercepted in intercepted_events.iter() {
+        commands
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgb(1., 0.9, 0.5),
+                    custom_size: Some(Vec2::splat(consts::SPARK_SIZE)),
+                    ..Default::default()
+                },
+                transform: Transform {
+                    translation: intercepted.position,
+                    rotation: Quat::from_rotation_z(std::f32::consts::FRAC_PI_4),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(Spark(Timer::from_seconds(
+                consts::SPARK_DURATION,
+                TimerMode::Once,
+            )));
+    }
+}
 
-                break;
-            }
+fn spark_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Spark, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut spark, mut transform, mut sprite) in query.iter_mut() {
+        if spark.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
         }
+
+        let left = spark.0.percent_left();
+        transform.scale = Vec3::splat(left);
+        sprite.color.set_a(left);
     }
 }
 
 fn explosion_to_spawn_system(
     mut commands: Commands,
+    mut pools: ResMut<Pools>,
     game_textures: Res<GameTextures>,
     query: Query<(Entity, &ExplosionToSpawn)>,
 ) {
     for (explosion_spawn_entity, explosion_to_spawn) in query.iter() {
-        // spawn the explosion sprite
-        commands
-            .spawn(SpriteSheetBundle {
+        // spawn the explosion sprite, or restart a parked one
+        pools.acquire(&mut commands, PoolKind::Explosion).insert((
+            SpriteSheetBundle {
                 texture_atlas: game_textures.explosion.clone(),
                 transform: Transform {
                     translation: explosion_to_spawn.0,
                     ..Default::default()
                 },
                 ..Default::default()
-            })
-            .insert(Explosion)
-            .insert(ExplosionTimer::default());
+            },
+            Explosion,
+            ExplosionTimer::default(),
+            Pooled::new(PoolKind::Explosion),
+        ));
 
         commands.entity(explosion_spawn_entity).despawn();
     }
 }
 
 fn explosion_animation_system(
-    mut commands: Commands,
-    settings: Res<Settings>,
     time: Res<Time>,
-    asset_server: Res<AssetServer>,
-    audio: Res<Audio>,
-    mut query: Query<(Entity, &mut ExplosionTimer, &mut TextureAtlasSprite), With<Explosion>>,
+    mut sfx: EventWriter<PlaySfx>,
+    mut recycle: EventWriter<Recycle>,
+    mut query: Query<
+        (
+            Entity,
+            &mut ExplosionTimer,
+            &mut TextureAtlasSprite,
+            &Pooled,
+        ),
+        With<Explosion>,
+    >,
 ) {
-    for (entity, mut timer, mut sprite) in query.iter_mut() {
+    for (entity, mut timer, mut sprite, pooled) in query.iter_mut() {
+        if !pooled.active {
+            continue;
+        }
+
         timer.0.tick(time.delta());
         if timer.0.finished() {
-            audio_play::explosion(sprite.index, &settings, &asset_server, &audio);
-            sprite.index += 1; // move to next sprite cell
-            if sprite.index >= consts::EXPLOSION_LEN {
-                commands.entity(entity).despawn();
+            if sprite.index == 0 {
+                sfx.send(PlaySfx::Explosion);
+            }
+            // the last cell stays until the pool hides it, the atlas has no cell past it
+            if sprite.index + 1 >= consts::EXPLOSION_LEN {
+                recycle.send(Recycle(entity));
+            } else {
+                sprite.index += 1; // move to next sprite cell
             }
         }
     }
@@ -307,48 +453,50 @@ fn explosion_animation_system(
 
 fn game_over_system(
     mut commands: Commands,
-    settings: Res<Settings>,
-    asset_server: Res<AssetServer>,
-    win_size: Res<WinSize>,
-    audio: Res<Audio>,
+    fonts: Res<FontRegistry>,
+    locale: Res<Locale>,
     kb: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    settings: Res<Settings>,
     mut exit: EventWriter<AppExit>,
     mut game_state: ResMut<GameState>,
     mut player_state: ResMut<PlayerState>,
-    mut text_set: ParamSet<(
-        Query<Entity, With<GameOverText>>,
-        Query<&mut Text, With<LifeText>>,
-        Query<&mut Text, With<CurrentScoreText>>,
-        Query<&mut Text, With<TotalScoreText>>,
-        Query<&mut Text, With<HistoryScoreText>>,
-    )>,
+    mut enemy_state: ResMut<EnemyState>,
+    mut run_stats: ResMut<RunStats>,
+    mut formation_maker: ResMut<FormationMaker>,
+    mut director: ResMut<Director>,
+    root_query: Query<Entity, With<PlayfieldRoot>>,
+    game_over_query: Query<Entity, With<GameOverText>>,
 ) {
     if !game_state.is_over {
         return;
     }
     if !game_state.show_over {
         game_state.show_over = true;
-        text::game_over_text_spawn(&mut commands, &asset_server, &win_size);
-        HistoryScoreText::update(text_set.p4(), player_state.total_score);
-        audio_play::game_over(&settings, &asset_server, &audio);
+        text::game_over_text_spawn(
+            &mut commands,
+            &fonts,
+            &locale,
+            &run_stats,
+            root_query.single(),
+        );
     }
 
     if kb.just_pressed(KeyCode::P) {
         // despawn game over text
-        let game_over_text = text_set.p0();
-        for entity in game_over_text.iter() {
+        for entity in game_over_query.iter() {
             // despawn_recursive 消除警告
             commands.entity(entity).despawn_recursive();
         }
-        game_state.reset();
-        player_state.replay();
-
-        // update life text
-        LifeText::update(text_set.p1(), player_state.lives);
-
-        // update score text
-        CurrentScoreText::update(text_set.p2(), player_state.current_score);
-        TotalScoreText::update(text_set.p3(), player_state.total_score);
+        game_state.reset(settings.difficulty);
+        // so does the console `timescale`
+        game_state.cheated |= time.relative_speed() != 1.;
+        player_state.replay(settings.difficulty);
+        // back to the first level, the enemies still on screen stay counted
+        enemy_state.update(GameLevel::Basic);
+        *run_stats = RunStats::default();
+        formation_maker.reset();
+        *director = Director::default();
     } else if kb.just_pressed(KeyCode::Escape) {
         exit.send(AppExit);
     }
@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+
+use crate::{
+    consts,
+    entity::{AppState, GameAudio, GameLevel, GameState, PlayerState, Settings},
+    events::{GameSet, LevelUp},
+};
+
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MusicPlayer::default())
+            .add_system(music_duck_system.in_set(GameSet::Present))
+            .add_system(music_track_system.run_if(not(in_state(AppState::Loading))))
+            .add_system(music_volume_system.after(music_track_system));
+    }
+}
+
+/// Background music, one looping track per stage of the game
+#[derive(Clone, Copy, PartialEq)]
+pub enum MusicTrack {
+    Calm,
+    Battle,
+    Boss,
+    GameOver,
+}
+
+impl MusicTrack {
+    fn from_level(level: GameLevel) -> Self {
+        match level {
+            GameLevel::Basic | GameLevel::Middle => MusicTrack::Calm,
+            GameLevel::Strong | GameLevel::Powerful => MusicTrack::Battle,
+            GameLevel::Invincible => MusicTrack::Boss,
+        }
+    }
+
+    fn source(&self, game_audio: &GameAudio) -> Handle<AudioSource> {
+        match self {
+            MusicTrack::Calm => game_audio.music_calm.clone(),
+            MusicTrack::Battle => game_audio.music_battle.clone(),
+            MusicTrack::Boss => game_audio.music_boss.clone(),
+            MusicTrack::GameOver => game_audio.music_game_over.clone(),
+        }
+    }
+}
+
+/// A playing track, `fade` goes from 0. (silent) to 1. (full volume)
+struct MusicChannel {
+    track: MusicTrack,
+    sink: Handle<AudioSink>,
+    fade: f32,
+}
+
+/// Resource - the current track and the ones still fading out
+#[derive(Default, Resource)]
+pub struct MusicPlayer {
+    current: Option<MusicChannel>,
+    fading_out: Vec<MusicChannel>,
+    duck: Option<Timer>,
+}
+
+/// Duck the music under the level upgrade sting
+fn music_duck_system(mut music: ResMut<MusicPlayer>, mut level_up_events: EventReader<LevelUp>) {
+    if level_up_events.iter().count() > 0 {
+        music.duck = Some(Timer::new(consts::MUSIC_DUCK_DURATION, TimerMode::Once));
+    }
+}
+
+fn music_track_system(
+    audio: Res<Audio>,
+    game_audio: Res<GameAudio>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    game_state: Res<GameState>,
+    player_state: Res<PlayerState>,
+    mut music: ResMut<MusicPlayer>,
+) {
+    let track = if game_state.is_over {
+        MusicTrack::GameOver
+    } else {
+        MusicTrack::from_level(player_state.get_game_level())
+    };
+    if music.current.as_ref().map(|channel| channel.track) == Some(track) {
+        return;
+    }
+
+    // start the new track silent, the volume system fades it in and the old one out
+    let sink = audio_sinks.get_handle(audio.play_with_settings(
+        track.source(&game_audio),
+        PlaybackSettings::LOOP.with_volume(0.),
+    ));
+    let channel = MusicChannel {
+        track,
+        sink,
+        fade: 0.,
+    };
+    if let Some(old) = music.current.replace(channel) {
+        music.fading_out.push(old);
+    }
+}
+
+fn music_volume_system(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    mut music: ResMut<MusicPlayer>,
+) {
+    let music = &mut *music;
+    let step = time.delta_seconds() / consts::MUSIC_CROSSFADE.as_secs_f32();
+
+    let mut duck = 1.;
+    if let Some(timer) = music.duck.as_mut() {
+        if timer.tick(time.delta()).finished() {
+            music.duck = None;
+        } else {
+            duck = consts::MUSIC_DUCK_VOLUME;
+        }
+    }
+    let volume = if settings.mute {
+        0.
+    } else {
+        consts::MUSIC_VOLUME * settings.volume * duck
+    };
+
+    if let Some(current) = music.current.as_mut() {
+        current.fade = (current.fade + step).min(1.);
+        if let Some(sink) = audio_sinks.get(&current.sink) {
+            sink.set_volume(volume * current.fade);
+        }
+    }
+
+    // keep a faded track until its sink exists, dropping the handle alone would not stop it
+    music.fading_out.retain_mut(|channel| {
+        channel.fade = (channel.fade - step).max(0.);
+        match audio_sinks.get(&channel.sink) {
+            Some(sink) if channel.fade == 0. => {
+                sink.stop();
+                false
+            }
+            Some(sink) => {
+                sink.set_volume(volume * channel.fade);
+                true
+            }
+            None => true,
+        }
+    });
+}
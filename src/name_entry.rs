@@ -0,0 +1,209 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{NameEntryScreen, NameEntryText, PlayfieldRoot, TextFonts},
+    consts::{NAME_CHARS, NAME_LEN, SIDE_MARGIN_PX},
+    entity::{AppState, Difficulty, GameState, Leaderboard},
+    events::{GameSet, NewHighScore},
+    fonts::{FontRegistry, FontStyle},
+    locale::Locale,
+    storage,
+};
+
+pub struct NameEntryPlugin;
+
+impl Plugin for NameEntryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(name_entry_start_system.in_set(GameSet::Present))
+            .add_system(name_entry_spawn_system.in_schedule(OnEnter(AppState::NameEntry)))
+            .add_system(name_entry_despawn_system.in_schedule(OnExit(AppState::NameEntry)))
+            .add_systems(
+                (name_entry_input_system, name_entry_text_system)
+                    .chain()
+                    .in_set(OnUpdate(AppState::NameEntry)),
+            );
+    }
+}
+
+/// Resource - the initials being entered for the leaderboard entry at `rank` of `difficulty`
+#[derive(Resource)]
+struct NameEntry {
+    difficulty: Difficulty,
+    rank: usize,
+    // indexes into `NAME_CHARS`
+    letters: [usize; NAME_LEN],
+    cursor: usize,
+}
+
+impl NameEntry {
+    fn new(difficulty: Difficulty, rank: usize) -> Self {
+        Self {
+            difficulty,
+            rank,
+            letters: [0; NAME_LEN],
+            cursor: 0,
+        }
+    }
+
+    fn cycle(&mut self, step: isize) {
+        let len = NAME_CHARS.len() as isize;
+        let letter = &mut self.letters[self.cursor];
+        *letter = (*letter as isize + step).rem_euclid(len) as usize;
+    }
+
+    fn name(&self) -> String {
+        self.letters
+            .iter()
+            .map(|&idx| NAME_CHARS.as_bytes()[idx] as char)
+            .collect()
+    }
+}
+
+fn name_entry_start_system(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    mut new_high_score_events: EventReader<NewHighScore>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if let Some(new_high_score) = new_high_score_events.iter().last() {
+        commands.insert_resource(NameEntry::new(game_state.difficulty, new_high_score.rank));
+        next_state.set(AppState::NameEntry);
+    }
+}
+
+fn name_entry_spawn_system(
+    mut commands: Commands,
+    fonts: Res<FontRegistry>,
+    locale: Res<Locale>,
+    root_query: Query<Entity, With<PlayfieldRoot>>,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            z_index: ZIndex::Global(20),
+            ..default()
+        })
+        .with_children(|builder| {
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(SIDE_MARGIN_PX * 2.)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.8)),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder
+                        .spawn(TextBundle::from_sections([TextSection::new(
+                            locale.strings().name_entry_title,
+                            fonts.style(&locale, FontStyle::Title, Color::WHITE),
+                        )]))
+                        .insert(TextFonts(vec![FontStyle::Title]));
+                    builder
+                        .spawn(TextBundle::from_sections((0..NAME_LEN).map(|_| {
+                            TextSection::new(
+                                "",
+                                fonts.style(&locale, FontStyle::Title, Color::GOLD),
+                            )
+                        })))
+                        .insert(TextFonts(vec![FontStyle::Title; NAME_LEN]))
+                        .insert(NameEntryText);
+                    builder
+                        .spawn(
+                            TextBundle::from_sections([TextSection::new(
+                                locale.strings().name_entry_prompt,
+                                fonts.style(&locale, FontStyle::Prompt, Color::GRAY),
+                            )])
+                            .with_text_alignment(TextAlignment::Center),
+                        )
+                        .insert(TextFonts(vec![FontStyle::Prompt]));
+                });
+        })
+        .insert(NameEntryScreen)
+        .set_parent(root_query.single());
+}
+
+fn name_entry_despawn_system(mut commands: Commands, query: Query<Entity, With<NameEntryScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<NameEntry>();
+}
+
+/// Arrow keys or d-pad pick the letters, typing a letter sets it directly
+fn name_entry_input_system(
+    kb: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mut characters: EventReader<ReceivedCharacter>,
+    mut entry: ResMut<NameEntry>,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let pad = |button_type| {
+        gamepads
+            .iter()
+            .any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+    };
+
+    for character in characters.iter() {
+        let c = character.char.to_ascii_uppercase();
+        if let Some(idx) = NAME_CHARS.find(c) {
+            let cursor = entry.cursor;
+            entry.letters[cursor] = idx;
+            entry.cursor = (cursor + 1).min(NAME_LEN - 1);
+        }
+    }
+
+    if kb.just_pressed(KeyCode::Up) || pad(GamepadButtonType::DPadUp) {
+        entry.cycle(-1);
+    } else if kb.just_pressed(KeyCode::Down) || pad(GamepadButtonType::DPadDown) {
+        entry.cycle(1);
+    }
+    if kb.any_just_pressed([KeyCode::Left, KeyCode::Back])
+        || pad(GamepadButtonType::DPadLeft)
+        || pad(GamepadButtonType::East)
+    {
+        entry.cursor = entry.cursor.saturating_sub(1);
+    } else if kb.just_pressed(KeyCode::Right) || pad(GamepadButtonType::DPadRight) {
+        entry.cursor = (entry.cursor + 1).min(NAME_LEN - 1);
+    }
+
+    if kb.any_just_pressed([KeyCode::Return, KeyCode::NumpadEnter])
+        || pad(GamepadButtonType::South)
+        || pad(GamepadButtonType::Start)
+    {
+        leaderboard.set_name(entry.difficulty, entry.rank, entry.name());
+        storage::save("leaderboard", &*leaderboard);
+        next_state.set(AppState::InGame);
+    }
+}
+
+/// The letter under the cursor blinks
+fn name_entry_text_system(
+    time: Res<Time>,
+    entry: Res<NameEntry>,
+    mut query: Query<&mut Text, With<NameEntryText>>,
+) {
+    let blink = (time.elapsed_seconds() * 4.).sin() > 0.;
+    let name = entry.name();
+    for mut text in &mut query {
+        for (i, (section, c)) in text.sections.iter_mut().zip(name.chars()).enumerate() {
+            section.value = format!(" {} ", c);
+            section.style.color = if i == entry.cursor && blink {
+                Color::WHITE
+            } else {
+                Color::GOLD
+            };
+        }
+    }
+}
@@ -1,13 +1,18 @@
 use std::time::Duration;
 
 use bevy::{prelude::*, time::common_conditions::on_timer};
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
 use crate::{
-    audio_play,
-    components::{CurrentScoreText, FromPlayer, Laser, Movable, Player, SpriteSize, Velocity},
-    consts::{self, PLAYER_RESPAWN_DELAY},
-    entity::{GameLevel, GameState, Settings},
+    components::{
+        Bounded, Collider, FromPlayer, Laser, Movable, Player, PoolKind, Pooled, SpriteSize,
+        Velocity,
+    },
+    consts,
+    entity::{AppState, GameLevel, GameState, Settings},
+    events::{GameOver, GameSet, LaserFired, PlayerDied, PlayerHit},
+    pool::Pools,
+    utils::{self, RngStream},
     GameTextures, PlayerState, WinSize,
 };
 
@@ -16,9 +21,17 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(PlayerState::default())
-            .add_system(player_spawn_system.run_if(on_timer(Duration::from_millis(500))))
-            .add_system(player_fire_system.run_if(player_fire_criteria))
-            .add_system(player_keyboard_event_system);
+            .add_systems(
+                (
+                    player_spawn_system.run_if(on_timer(Duration::from_millis(500))),
+                    player_fire_system
+                        .run_if(player_fire_criteria)
+                        .in_set(GameSet::Emit),
+                    player_keyboard_event_system,
+                )
+                    .in_set(OnUpdate(AppState::InGame)),
+            )
+            .add_system(player_hit_system.in_set(GameSet::React));
     }
 }
 
@@ -29,7 +42,6 @@ fn player_spawn_system(
     time: Res<Time>,
     game_textures: Res<GameTextures>,
     win_size: Res<WinSize>,
-    text_query: Query<&mut Text, With<CurrentScoreText>>,
 ) {
     if game_state.is_over {
         return;
@@ -38,7 +50,9 @@ fn player_spawn_system(
     let now = time.elapsed_seconds_f64();
     let last_shot = player_state.last_shot;
 
-    if !player_state.on && (last_shot == -1. || now > last_shot + PLAYER_RESPAWN_DELAY) {
+    if !player_state.on
+        && (last_shot == -1. || now > last_shot + game_state.difficulty.respawn_delay())
+    {
         // add player
         let bottom = -win_size.h / 2.;
         commands
@@ -61,22 +75,53 @@ fn player_spawn_system(
             })
             .insert(Player)
             .insert(SpriteSize::from(consts::PLAYER_SIZE))
+            .insert(Collider::new(
+                consts::PLAYER_HITBOX,
+                consts::LAYER_PLAYER,
+                consts::LAYER_ENEMY,
+            ))
             .insert(Movable {
                 auto_despawn: false,
             })
-            .insert(Velocity { x: 0., y: 0. });
+            .insert(Velocity { x: 0., y: 0. })
+            .insert(Bounded::default());
 
         player_state.spawned();
-        CurrentScoreText::update(text_query, player_state.current_score);
     }
 }
 
+/// Everything a player laser is made of, also overwrites a recycled one
+pub fn player_laser(texture: Handle<Image>, translation: Vec3, mask: u32) -> impl Bundle {
+    (
+        SpriteBundle {
+            texture,
+            transform: Transform {
+                translation,
+                scale: Vec3::new(consts::SPRITE_SCALE, consts::SPRITE_SCALE, 1.),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Laser,
+        FromPlayer,
+        SpriteSize::from(consts::PLAYER_LASER_SIZE),
+        Collider::new(
+            consts::PLAYER_LASER_HITBOX,
+            consts::LAYER_PLAYER_LASER,
+            mask,
+        ),
+        Movable { auto_despawn: true },
+        Velocity { x: 0., y: 1. },
+        Pooled::new(PoolKind::PlayerLaser),
+    )
+}
+
 fn player_fire_system(
     mut commands: Commands,
-    settings: Res<Settings>,
     player_state: ResMut<PlayerState>,
-    asset_server: Res<AssetServer>,
-    audio: Res<Audio>,
+    settings: Res<Settings>,
+    mut pools: ResMut<Pools>,
+    mut fired: EventWriter<LaserFired>,
     kb: Res<Input<KeyCode>>,
     game_textures: Res<GameTextures>,
     query: Query<&Transform, With<Player>>,
@@ -85,23 +130,22 @@ fn player_fire_system(
         if kb.pressed(KeyCode::Space) {
             let (x, y) = (player_tf.translation.x, player_tf.translation.y);
             let mut x_offset = consts::PLAYER_SIZE.0 / 2. * consts::SPRITE_SCALE - 5.;
+            let mut count = 0;
+            let mask = if settings.intercept {
+                consts::LAYER_ENEMY | consts::LAYER_ENEMY_LASER
+            } else {
+                consts::LAYER_ENEMY
+            };
 
             let mut spawn_laser = |x_offset: f32| {
-                commands
-                    .spawn(SpriteBundle {
-                        texture: game_textures.palyer_laser.clone(),
-                        transform: Transform {
-                            translation: Vec3::new(x + x_offset, y + 15., 0.),
-                            scale: Vec3::new(consts::SPRITE_SCALE, consts::SPRITE_SCALE, 1.),
-                            ..Default::default()
-                        },
-                        ..Default::default()
-                    })
-                    .insert(Laser)
-                    .insert(FromPlayer)
-                    .insert(SpriteSize::from(consts::PLAYER_LASER_SIZE))
-                    .insert(Movable { auto_despawn: true })
-                    .insert(Velocity { x: 0., y: 1. });
+                count += 1;
+                pools
+                    .acquire(&mut commands, PoolKind::PlayerLaser)
+                    .insert(player_laser(
+                        game_textures.palyer_laser.clone(),
+                        Vec3::new(x + x_offset, y + 15., 0.),
+                        mask,
+                    ));
             };
 
             // FIXME 代码待重构
@@ -137,51 +181,61 @@ fn player_fire_system(
                     spawn_laser(-x_offset);
                 }
             }
-            audio_play::fire_shot(
-                player_state.get_fire_level(),
-                &settings,
-                &asset_server,
-                &audio,
-            );
+            fired.send(LaserFired {
+                level: player_state.get_fire_level(),
+                count,
+            });
         }
     }
 }
 
-fn player_fire_criteria() -> bool {
-    if thread_rng().gen_bool(10. / 60.) {
-        true
-    } else {
-        false
+/// The ship goes down, a lost last life ends the game
+fn player_hit_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player_state: ResMut<PlayerState>,
+    mut game_state: ResMut<GameState>,
+    mut hit_events: EventReader<PlayerHit>,
+    mut died: EventWriter<PlayerDied>,
+    mut game_over: EventWriter<GameOver>,
+) {
+    for hit in hit_events.iter() {
+        commands.entity(hit.player).despawn();
+
+        let lives = player_state.shot(time.elapsed_seconds_f64());
+        died.send(PlayerDied {
+            position: hit.position,
+        });
+        if lives == 0 {
+            game_state.is_over = true;
+            game_over.send(GameOver {
+                score: player_state.points,
+            });
+        }
     }
 }
 
+fn player_fire_criteria() -> bool {
+    utils::rng(RngStream::PlayerFire).gen_bool(10. / 60.)
+}
+
+/// Bounds are kept by the `Bounded` component, this only steers
 fn player_keyboard_event_system(
     kb: Res<Input<KeyCode>>,
-    win_size: Res<WinSize>,
-    mut query: Query<(&Transform, &mut Velocity), With<Player>>,
+    mut query: Query<&mut Velocity, With<Player>>,
 ) {
-    if let Ok((t, mut velocity)) = query.get_single_mut() {
-        let x_half_size = win_size.w / 2.;
-        let y_half_size = win_size.h / 2.;
-        velocity.x = if (kb.pressed(KeyCode::Left) || kb.pressed(KeyCode::A))
-            && t.translation.x > -x_half_size
-        {
+    if let Ok(mut velocity) = query.get_single_mut() {
+        velocity.x = if kb.pressed(KeyCode::Left) || kb.pressed(KeyCode::A) {
             -0.7
-        } else if (kb.pressed(KeyCode::Right) || kb.pressed(KeyCode::D))
-            && t.translation.x < x_half_size
-        {
+        } else if kb.pressed(KeyCode::Right) || kb.pressed(KeyCode::D) {
             0.7
         } else {
             0.
         };
 
-        velocity.y = if (kb.pressed(KeyCode::Up) || kb.pressed(KeyCode::W))
-            && t.translation.y < y_half_size
-        {
+        velocity.y = if kb.pressed(KeyCode::Up) || kb.pressed(KeyCode::W) {
             1.
-        } else if (kb.pressed(KeyCode::Down) || kb.pressed(KeyCode::S))
-            && t.translation.y > -y_half_size
-        {
+        } else if kb.pressed(KeyCode::Down) || kb.pressed(KeyCode::S) {
             -1.
         } else {
             0.
@@ -0,0 +1,82 @@
+use bevy::{prelude::*, render::camera::ScalingMode, window::PrimaryWindow};
+
+use crate::{
+    components::PlayfieldRoot,
+    consts::{LETTERBOX_COLOR, PLAYFIELD_SIZE},
+};
+
+pub struct PlayfieldPlugin;
+
+impl Plugin for PlayfieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(playfield_spawn_system)
+            .add_system(playfield_layout_system);
+    }
+}
+
+fn playfield_spawn_system(mut commands: Commands) {
+    // camera, always shows the whole playfield whatever the window aspect
+    let mut camera = Camera2dBundle::default();
+    camera.projection.scaling_mode = ScalingMode::AutoMin {
+        min_width: PLAYFIELD_SIZE.0,
+        min_height: PLAYFIELD_SIZE.1,
+    };
+    commands.spawn(camera);
+
+    // letterbox bars, hide what moves outside the playfield (e.g. enemies flying in)
+    const BAR: f32 = 10000.;
+    let (half_w, half_h) = (PLAYFIELD_SIZE.0 / 2., PLAYFIELD_SIZE.1 / 2.);
+    for (x, y, size) in [
+        (-half_w - BAR / 2., 0., Vec2::new(BAR, BAR * 2.)),
+        (half_w + BAR / 2., 0., Vec2::new(BAR, BAR * 2.)),
+        (0., -half_h - BAR / 2., Vec2::new(BAR * 2., BAR)),
+        (0., half_h + BAR / 2., Vec2::new(BAR * 2., BAR)),
+    ] {
+        commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: LETTERBOX_COLOR,
+                custom_size: Some(size),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(x, y, 900.),
+            ..Default::default()
+        });
+    }
+
+    // ui root, HUD and menus are children so they follow the playfield
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Px(PLAYFIELD_SIZE.0), Val::Px(PLAYFIELD_SIZE.1)),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(PlayfieldRoot);
+}
+
+/// Scale the ui like the camera does and center the ui root on the playfield,
+/// runs again on every window change (resize, display mode)
+fn playfield_layout_system(
+    mut ui_scale: ResMut<UiScale>,
+    window_query: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut root_query: Query<&mut Style, With<PlayfieldRoot>>,
+) {
+    let (Ok(window), Ok(mut style)) = (window_query.get_single(), root_query.get_single_mut())
+    else {
+        return;
+    };
+
+    let scale = (window.width() / PLAYFIELD_SIZE.0).min(window.height() / PLAYFIELD_SIZE.1);
+    if scale <= 0. {
+        // minimized
+        return;
+    }
+    ui_scale.scale = scale as f64;
+    style.position = UiRect {
+        left: Val::Px((window.width() / scale - PLAYFIELD_SIZE.0) / 2.),
+        top: Val::Px((window.height() / scale - PLAYFIELD_SIZE.1) / 2.),
+        ..default()
+    };
+}
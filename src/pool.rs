@@ -0,0 +1,237 @@
+use bevy::{ecs::system::EntityCommands, prelude::*, render::view::VisibilitySystems};
+
+use crate::{
+    components::{Collider, PoolKind, Pooled, Velocity},
+    events::Recycle,
+};
+
+#[cfg(feature = "bench-pool")]
+pub use self::bench::bench;
+
+pub struct PoolPlugin;
+
+impl Plugin for PoolPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Pools::default())
+            // after the frame's gameplay, so a recycled entity is never reused the same frame,
+            // and before the visibility check, so it is not drawn once more
+            .add_system(
+                recycle_system
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(VisibilitySystems::CheckVisibility),
+            );
+    }
+}
+
+/// Resource - parked lasers and explosions, waiting to be reused instead of spawned
+#[derive(Resource, Default)]
+pub struct Pools {
+    free: [Vec<Entity>; PoolKind::COUNT],
+    pub spawned: u32,
+    pub reused: u32,
+}
+
+impl Pools {
+    /// A parked entity to overwrite with a fresh bundle, or a new empty one
+    pub fn acquire<'w, 's, 'a>(
+        &mut self,
+        commands: &'a mut Commands<'w, 's>,
+        kind: PoolKind,
+    ) -> EntityCommands<'w, 's, 'a> {
+        match self.free[kind as usize].pop() {
+            Some(entity) => {
+                self.reused += 1;
+                commands.entity(entity)
+            }
+            None => {
+                self.spawned += 1;
+                commands.spawn_empty()
+            }
+        }
+    }
+
+    pub fn parked(&self, kind: PoolKind) -> usize {
+        self.free[kind as usize].len()
+    }
+}
+
+/// Parks the entities instead of despawning them, only data changes so none moves archetype
+fn recycle_system(
+    mut commands: Commands,
+    mut pools: ResMut<Pools>,
+    mut recycle_events: EventReader<Recycle>,
+    mut query: Query<(
+        &mut Pooled,
+        &mut Visibility,
+        Option<&mut Velocity>,
+        Option<&mut Collider>,
+    )>,
+) {
+    for Recycle(entity) in recycle_events.iter() {
+        match query.get_mut(*entity) {
+            Ok((mut pooled, mut visibility, velocity, collider)) => {
+                if !pooled.active {
+                    continue;
+                }
+                pooled.active = false;
+                *visibility = Visibility::Hidden;
+                if let Some(mut velocity) = velocity {
+                    velocity.x = 0.;
+                    velocity.y = 0.;
+                }
+                if let Some(mut collider) = collider {
+                    collider.layer = 0;
+                    collider.mask = 0;
+                }
+                pools.free[pooled.kind as usize].push(*entity);
+            }
+            Err(_) => {
+                if let Some(mut entity) = commands.get_entity(*entity) {
+                    entity.despawn();
+                }
+            }
+        }
+    }
+}
+
+// region: --- Benchmark
+
+/// Kept out of the game binary, the counting allocator would tax every allocation of a normal run
+#[cfg(feature = "bench-pool")]
+mod bench {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        collections::VecDeque,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Instant,
+    };
+
+    use bevy::prelude::*;
+
+    use super::{PoolPlugin, Pools};
+    use crate::{components::PoolKind, consts, events::Recycle, player::player_laser};
+
+    /// Counts heap allocations for `--bench-pool`, otherwise forwards to the system allocator
+    struct CountingAllocator;
+
+    static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    const BENCH_FRAMES: u32 = 600;
+    const BENCH_LASERS_PER_FRAME: usize = 300;
+    const BENCH_LASER_LIFETIME: u32 = 60;
+
+    #[derive(Resource)]
+    struct BenchLasers {
+        pooled: bool,
+        frame: u32,
+        alive: VecDeque<(u32, Entity)>,
+        spawned: u32,
+    }
+
+    /// Fires a volley of player lasers every frame and retires the ones older than their lifetime
+    fn bench_laser_system(
+        mut commands: Commands,
+        mut pools: ResMut<Pools>,
+        mut bench: ResMut<BenchLasers>,
+        mut recycle: EventWriter<Recycle>,
+    ) {
+        bench.frame += 1;
+        while let Some(&(born, entity)) = bench.alive.front() {
+            if born + BENCH_LASER_LIFETIME > bench.frame {
+                break;
+            }
+            bench.alive.pop_front();
+            if bench.pooled {
+                recycle.send(Recycle(entity));
+            } else {
+                commands.entity(entity).despawn();
+            }
+        }
+
+        for i in 0..BENCH_LASERS_PER_FRAME {
+            let translation = Vec3::new(i as f32, bench.frame as f32, 0.);
+            let mut laser = if bench.pooled {
+                pools.acquire(&mut commands, PoolKind::PlayerLaser)
+            } else {
+                bench.spawned += 1;
+                commands.spawn_empty()
+            };
+            laser.insert(player_laser(
+                Handle::default(),
+                translation,
+                consts::LAYER_ENEMY,
+            ));
+            let entity = laser.id();
+            let frame = bench.frame;
+            bench.alive.push_back((frame, entity));
+        }
+    }
+
+    /// `--bench-pool`: the same stream of lasers in a headless app,
+    /// once spawned and despawned, once recycled through the pool
+    pub fn bench() {
+        println!("strategy           ms/frame    allocations/frame    entities spawned");
+        for pooled in [false, true] {
+            let mut app = App::new();
+            app.add_plugins(MinimalPlugins)
+                .add_plugin(PoolPlugin)
+                .add_event::<Recycle>()
+                .insert_resource(BenchLasers {
+                    pooled,
+                    frame: 0,
+                    alive: VecDeque::new(),
+                    spawned: 0,
+                })
+                .add_system(bench_laser_system);
+
+            // fill the screen before measuring
+            for _ in 0..BENCH_LASER_LIFETIME * 2 {
+                app.update();
+            }
+
+            let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+            let start = Instant::now();
+            for _ in 0..BENCH_FRAMES {
+                app.update();
+            }
+            let ms = start.elapsed().as_secs_f64() * 1000. / BENCH_FRAMES as f64;
+            let allocations =
+                (ALLOCATIONS.load(Ordering::Relaxed) - allocations) / BENCH_FRAMES as usize;
+
+            let world = &app.world;
+            let spawned = if pooled {
+                world.resource::<Pools>().spawned
+            } else {
+                world.resource::<BenchLasers>().spawned
+            };
+            println!(
+                "{:<16}    {:>8.3}    {:>17}    {:>16}",
+                if pooled { "pool" } else { "spawn/despawn" },
+                ms,
+                allocations,
+                spawned,
+            );
+        }
+    }
+}
+
+// endregion: --- Benchmark
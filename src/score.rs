@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{ScorePopup, TextFonts},
+    consts,
+    enemy::FormationMaker,
+    entity::{AppState, Combo, EnemyState, PlayerState},
+    events::{EnemyKilled, GameSet, LaserIntercepted, LevelUp, PlayerHit, ScoreGained},
+    fonts::{FontRegistry, FontStyle},
+    locale::{fill, Locale},
+};
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Combo::default())
+            .add_systems(
+                (
+                    score_enemy_killed_system,
+                    score_intercept_system,
+                    combo_reset_system,
+                )
+                    .in_set(GameSet::React),
+            )
+            .add_system(score_popup_spawn_system.in_set(GameSet::Present))
+            .add_systems(
+                (combo_decay_system, score_popup_system).in_set(OnUpdate(AppState::InGame)),
+            );
+    }
+}
+
+pub fn score_enemy_killed_system(
+    mut killed_events: EventReader<EnemyKilled>,
+    mut player_state: ResMut<PlayerState>,
+    mut enemy_state: ResMut<EnemyState>,
+    mut combo: ResMut<Combo>,
+    mut formation_maker: ResMut<FormationMaker>,
+    mut level_up: EventWriter<LevelUp>,
+    mut score_gained: EventWriter<ScoreGained>,
+) {
+    for killed in killed_events.iter() {
+        // kills drive the levels
+        player_state.increase_score();
+
+        // the enemies follow the game level
+        if enemy_state.update(player_state.get_game_level()) {
+            level_up.send(LevelUp(enemy_state.level));
+        }
+
+        // points drive the score
+        // a ram costs the ship the combo, it doesn't add to it
+        if !killed.rammed {
+            combo.kill();
+        }
+        let multiplier = combo.multiplier();
+        let points = killed.kind.points() * multiplier;
+
+        // shooting down every member of a formation earns a bonus
+        let bonus = if formation_maker.killed(killed.formation) {
+            consts::FORMATION_BONUS
+        } else {
+            0
+        };
+
+        player_state.points += points + bonus;
+        score_gained.send(ScoreGained {
+            position: killed.position,
+            points,
+            multiplier,
+            bonus,
+        });
+    }
+}
+
+/// Shooting down an enemy laser is worth a flat bonus, outside the combo
+fn score_intercept_system(
+    mut intercepted_events: EventReader<LaserIntercepted>,
+    mut player_state: ResMut<PlayerState>,
+    mut score_gained: EventWriter<ScoreGained>,
+) {
+    for intercepted in intercepted_events.iter() {
+        player_state.points += consts::INTERCEPT_BONUS;
+        score_gained.send(ScoreGained {
+            position: intercepted.position,
+            points: consts::INTERCEPT_BONUS,
+            multiplier: 1,
+            bonus: 0,
+        });
+    }
+}
+
+fn combo_reset_system(mut combo: ResMut<Combo>, mut hit_events: EventReader<PlayerHit>) {
+    if hit_events.iter().count() > 0 {
+        combo.reset();
+    }
+}
+
+fn combo_decay_system(time: Res<Time>, mut combo: ResMut<Combo>) {
+    combo.decay(time.delta());
+}
+
+fn score_popup_spawn_system(
+    mut commands: Commands,
+    fonts: Res<FontRegistry>,
+    locale: Res<Locale>,
+    mut score_events: EventReader<ScoreGained>,
+) {
+    for score in score_events.iter() {
+        let mut txt = format!("+{}", score.points);
+        if score.multiplier > 1 {
+            txt.push_str(format!(" x{}", score.multiplier).as_str());
+        }
+        if score.bonus > 0 {
+            txt.push_str(
+                format!("\n{}", fill(locale.strings().formation_bonus, score.bonus)).as_str(),
+            );
+        }
+        let color = if score.bonus > 0 {
+            Color::GOLD
+        } else {
+            Color::WHITE
+        };
+
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(txt, fonts.style(&locale, FontStyle::Hud, color))
+                    .with_alignment(TextAlignment::Center),
+                transform: Transform::from_translation(
+                    score.position.truncate().extend(consts::Z_COORDINATE + 1.),
+                ),
+                ..Default::default()
+            })
+            .insert(TextFonts(vec![FontStyle::Hud]))
+            .insert(ScorePopup(Timer::from_seconds(
+                consts::SCORE_POPUP_DURATION,
+                TimerMode::Once,
+            )));
+    }
+}
+
+/// Popups float up and fade out
+fn score_popup_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut ScorePopup, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut popup, mut transform, mut text) in query.iter_mut() {
+        if popup.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation.y += consts::SCORE_POPUP_SPEED * time.delta_seconds();
+        let alpha = popup.0.percent_left();
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
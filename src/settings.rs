@@ -1,21 +1,46 @@
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowMode},
+};
 
 use crate::{
-    components::AudioButton,
+    components::{AudioButton, PlayfieldRoot, SettingsScreen, SettingsText, TextFonts},
     consts::{self, COMMON_FONT_SIZE, SIDE_MARGIN_PX},
-    entity::Settings,
+    entity::{AppState, DisplayMode, GameAudio, GameState, Settings},
+    fonts::{FontRegistry, FontStyle},
+    locale::{fill, Locale},
+    storage,
 };
 
 pub struct SettingsPlugin;
 
 impl Plugin for SettingsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(settings_spawn)
-            .add_system(setting_audio_system);
+        app.insert_resource(storage::load::<Settings>("settings"))
+            .add_system(settings_spawn.in_schedule(OnExit(AppState::Loading)))
+            .add_system(settings_screen_spawn.in_schedule(OnEnter(AppState::Settings)))
+            .add_system(settings_screen_despawn.in_schedule(OnExit(AppState::Settings)))
+            .add_system(settings_screen_text_system.in_set(OnUpdate(AppState::Settings)))
+            .add_system(setting_difficulty_system.in_set(OnUpdate(AppState::Settings)))
+            .add_system(settings_screen_toggle_system)
+            .add_system(
+                setting_audio_system
+                    .run_if(not(in_state(AppState::NameEntry)))
+                    .run_if(not(in_state(AppState::Console))),
+            )
+            .add_system(setting_volume_system.run_if(not(in_state(AppState::Console))))
+            .add_system(setting_display_system)
+            .add_system(setting_apply_system)
+            .add_system(setting_save_system);
     }
 }
 
-fn settings_spawn(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn settings_spawn(
+    mut commands: Commands,
+    game_audio: Res<GameAudio>,
+    settings: Res<Settings>,
+    root_query: Query<Entity, With<PlayfieldRoot>>,
+) {
     commands
         .spawn(ButtonBundle {
             style: Style {
@@ -31,34 +56,224 @@ fn settings_spawn(mut commands: Commands, asset_server: Res<AssetServer>) {
             background_color: BackgroundColor(Color::GRAY),
             z_index: ZIndex::Global(10),
             image: UiImage {
-                texture: asset_server.load(consts::ICONS_VOICE_ON),
+                texture: voice_icon(&game_audio, &settings),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(AudioButton)
+        .set_parent(root_query.single());
+}
+
+fn voice_icon(game_audio: &GameAudio, settings: &Settings) -> Handle<Image> {
+    if settings.mute {
+        game_audio.voice_off.clone()
+    } else {
+        game_audio.voice_on.clone()
+    }
+}
+
+fn settings_screen_toggle_system(
+    kb: Res<Input<KeyCode>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if kb.just_pressed(KeyCode::Tab) {
+        match state.0 {
+            AppState::InGame => next_state.set(AppState::Settings),
+            AppState::Settings => next_state.set(AppState::InGame),
+            _ => {}
+        }
+    }
+}
+
+fn settings_screen_spawn(
+    mut commands: Commands,
+    fonts: Res<FontRegistry>,
+    locale: Res<Locale>,
+    root_query: Query<Entity, With<PlayfieldRoot>>,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
                 ..default()
             },
+            z_index: ZIndex::Global(20),
             ..default()
         })
-        .insert(AudioButton);
+        .with_children(|builder| {
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(SIDE_MARGIN_PX * 2.)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.8)),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder
+                        .spawn(TextBundle::from_sections([TextSection::new(
+                            locale.strings().settings_title,
+                            fonts.style(&locale, FontStyle::Title, Color::WHITE),
+                        )]))
+                        .insert(TextFonts(vec![FontStyle::Title]));
+                    builder
+                        .spawn(TextBundle::from_sections([TextSection::new(
+                            "",
+                            fonts.style(&locale, FontStyle::Prompt, Color::GOLD),
+                        )]))
+                        .insert(TextFonts(vec![FontStyle::Prompt]))
+                        .insert(SettingsText);
+                });
+        })
+        .insert(SettingsScreen)
+        .set_parent(root_query.single());
+}
+
+fn settings_screen_despawn(mut commands: Commands, query: Query<Entity, With<SettingsScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn settings_screen_text_system(
+    settings: Res<Settings>,
+    game_state: Res<GameState>,
+    locale: Res<Locale>,
+    mut query: Query<&mut Text, With<SettingsText>>,
+) {
+    let strings = locale.strings();
+    let sound = if settings.mute {
+        strings.off
+    } else {
+        strings.on
+    };
+    let volume = format!("{}%", (settings.volume * 100.).round());
+    let display = match settings.display_mode {
+        DisplayMode::Windowed => strings.windowed,
+        DisplayMode::Fullscreen => strings.fullscreen,
+    };
+    let mut difficulty = strings.difficulty(settings.difficulty).to_string();
+    if settings.difficulty != game_state.difficulty {
+        difficulty.push_str(format!(" {}", strings.settings_next_run).as_str());
+    }
+    let txt = [
+        fill(strings.settings_sound, sound),
+        fill(strings.settings_volume, volume),
+        fill(strings.settings_display, display),
+        fill(strings.settings_language, strings.language_name),
+        fill(strings.settings_difficulty, difficulty),
+        fill(
+            strings.settings_adaptive,
+            if settings.adaptive {
+                strings.on
+            } else {
+                strings.off
+            },
+        ),
+        fill(
+            strings.settings_intercept,
+            if settings.intercept {
+                strings.on
+            } else {
+                strings.off
+            },
+        ),
+        format!("\n{}", strings.settings_back),
+    ]
+    .join("\n");
+
+    for mut text in &mut query {
+        if text.sections[0].value != txt {
+            text.sections[0].value = txt.clone();
+        }
+    }
 }
 
 fn setting_audio_system(
-    asset_server: Res<AssetServer>,
+    kb: Res<Input<KeyCode>>,
     mut settings: ResMut<Settings>,
-    mut interaction_query: Query<
-        (&Interaction, &mut UiImage),
+    interaction_query: Query<
+        &Interaction,
         (Changed<Interaction>, (With<Button>, With<AudioButton>)),
     >,
 ) {
-    for (interaction, mut audio_icon) in &mut interaction_query {
-        match *interaction {
-            Interaction::Clicked => {
-                if audio_icon.texture == asset_server.load(consts::ICONS_VOICE_ON) {
-                    audio_icon.texture = asset_server.load(consts::ICONS_VOICE_OFF);
-                    settings.mute = true;
-                } else {
-                    audio_icon.texture = asset_server.load(consts::ICONS_VOICE_ON);
-                    settings.mute = false;
-                }
-            }
-            _ => {}
+    let clicked = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked || kb.just_pressed(KeyCode::M) {
+        settings.mute = !settings.mute;
+    }
+}
+
+fn setting_volume_system(kb: Res<Input<KeyCode>>, mut settings: ResMut<Settings>) {
+    if kb.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        settings.volume = (settings.volume - consts::VOLUME_STEP).max(0.);
+    } else if kb.any_just_pressed([KeyCode::Equals, KeyCode::NumpadAdd]) {
+        settings.volume = (settings.volume + consts::VOLUME_STEP).min(1.);
+    }
+}
+
+fn setting_display_system(kb: Res<Input<KeyCode>>, mut settings: ResMut<Settings>) {
+    if kb.just_pressed(KeyCode::F11) {
+        settings.display_mode = match settings.display_mode {
+            DisplayMode::Windowed => DisplayMode::Fullscreen,
+            DisplayMode::Fullscreen => DisplayMode::Windowed,
+        };
+    }
+}
+
+fn setting_difficulty_system(kb: Res<Input<KeyCode>>, mut settings: ResMut<Settings>) {
+    if kb.just_pressed(KeyCode::Left) {
+        settings.difficulty = settings.difficulty.prev();
+    } else if kb.just_pressed(KeyCode::Right) {
+        settings.difficulty = settings.difficulty.next();
+    }
+    if kb.just_pressed(KeyCode::D) {
+        settings.adaptive = !settings.adaptive;
+    }
+    if kb.just_pressed(KeyCode::I) {
+        settings.intercept = !settings.intercept;
+    }
+}
+
+/// Apply the settings to the window and the audio button, the playfield re-scales on window change
+fn setting_apply_system(
+    settings: Res<Settings>,
+    game_audio: Option<Res<GameAudio>>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut button_query: Query<&mut UiImage, With<AudioButton>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let mode = match settings.display_mode {
+        DisplayMode::Windowed => WindowMode::Windowed,
+        DisplayMode::Fullscreen => WindowMode::BorderlessFullscreen,
+    };
+    if let Ok(mut window) = window_query.get_single_mut() {
+        if window.mode != mode {
+            window.mode = mode;
+        }
+    }
+
+    if let Some(game_audio) = game_audio {
+        for mut image in &mut button_query {
+            image.texture = voice_icon(&game_audio, &settings);
         }
     }
 }
+
+fn setting_save_system(settings: Res<Settings>) {
+    if settings.is_changed() && !settings.is_added() {
+        storage::save("settings", &*settings);
+    }
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+use crate::{
+    entity::{AppState, Combo, GameState, PlayerState, RunStats},
+    events::{EnemyKilled, GameSet, LaserFired, PlayerHit},
+    score::score_enemy_killed_system,
+};
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RunStats::default())
+            // the kill's level and combo are counted by then
+            .add_system(
+                stats_event_system
+                    .in_set(GameSet::React)
+                    .after(score_enemy_killed_system),
+            )
+            .add_system(stats_time_system.in_set(OnUpdate(AppState::InGame)));
+    }
+}
+
+pub fn stats_event_system(
+    player_state: Res<PlayerState>,
+    combo: Res<Combo>,
+    mut run_stats: ResMut<RunStats>,
+    mut fired_events: EventReader<LaserFired>,
+    mut killed_events: EventReader<EnemyKilled>,
+    mut hit_events: EventReader<PlayerHit>,
+) {
+    for fired in fired_events.iter() {
+        run_stats.shots_fired += fired.count;
+        run_stats.highest_fire_level = run_stats.highest_fire_level.max(fired.level);
+    }
+    // the frame's kills are already in the total, count back to the level of each
+    let killed: Vec<&EnemyKilled> = killed_events.iter().collect();
+    let first_kill = player_state.total_score.saturating_sub(killed.len() as u32) + 1;
+    for (kills, killed) in (first_kill..).zip(killed) {
+        // a ram isn't a shot that hit
+        if !killed.rammed {
+            run_stats.hits += 1;
+        }
+        run_stats.kills_per_level[PlayerState::compute_game_level(kills) as usize] += 1;
+    }
+    run_stats.deaths += hit_events.iter().count() as u32;
+    if combo.kills > run_stats.longest_combo {
+        run_stats.longest_combo = combo.kills;
+    }
+}
+
+fn stats_time_system(time: Res<Time>, game_state: Res<GameState>, mut run_stats: ResMut<RunStats>) {
+    if !game_state.is_over {
+        run_stats.time_survived += time.delta_seconds();
+    }
+}
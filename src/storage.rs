@@ -0,0 +1,42 @@
+use std::{env, fs, path::PathBuf};
+
+use bevy::prelude::{error, warn};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Directory the config and records are saved in
+fn save_dir() -> PathBuf {
+    let base = env::var_os("APPDATA")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("XDG_DATA_HOME").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .unwrap_or_else(|| PathBuf::from("."));
+    base.join("bevy_invaders")
+}
+
+/// Load `<name>.ron`, or the default when it doesn't exist or can't be parsed
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> T {
+    let path = save_dir().join(format!("{}.ron", name));
+    match fs::read_to_string(&path) {
+        Ok(content) => ron::from_str(&content).unwrap_or_else(|err| {
+            warn!("can't parse `{}`, using defaults: {}", path.display(), err);
+            T::default()
+        }),
+        Err(_) => T::default(),
+    }
+}
+
+/// Save `<name>.ron`, failures are logged and otherwise ignored
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    let dir = save_dir();
+    let path = dir.join(format!("{}.ron", name));
+    let result = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|err| err.to_string())
+        .and_then(|content| {
+            fs::create_dir_all(&dir)
+                .and_then(|_| fs::write(&path, content))
+                .map_err(|err| err.to_string())
+        });
+    if let Err(err) = result {
+        error!("can't save `{}`: {}", path.display(), err);
+    }
+}
@@ -1,29 +1,50 @@
-use std::sync::Mutex;
-
-use bevy::prelude::*;
+use bevy::{prelude::*, window::PrimaryWindow};
 
 use crate::{
-    components::{CurrentScoreText, GameOverText, HistoryScoreText, LifeText, TotalScoreText},
-    consts::{self, COMMON_FONT_SIZE, HISTORY_LEN, MIDDLE_FONT_SIZE, SIDE_MARGIN_PX},
-    entity::WinSize,
+    components::{
+        CurrentScoreText, GameOverText, HighScoreText, HistoryScoreText, LifeText, LocalizedText,
+        PlayfieldRoot, RunSummaryText, ScoreText, TextFonts, TotalScoreText,
+    },
+    consts::{HISTORY_LEN, SIDE_MARGIN_PX},
+    entity::{
+        AppState, Combo, Difficulty, GameState, Leaderboard, LeaderboardEntry, PlayerState,
+        RunStats,
+    },
+    events::{GameSet, NewHighScore},
+    fonts::{FontRegistry, FontStyle},
+    locale::{fill, Locale, Strings},
 };
 
 pub struct TextPlugin;
 
 impl Plugin for TextPlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(score_text_spawn_system)
-            .add_startup_system(lives_text_spawn_system);
+        app.add_systems(
+            (score_text_spawn_system, lives_text_spawn_system)
+                .in_schedule(OnExit(AppState::Loading)),
+        )
+        .add_systems(
+            (
+                hud_text_system,
+                history_text_system,
+                run_summary_text_system,
+                high_score_spawn_system,
+                high_score_text_system,
+            )
+                .in_set(GameSet::Present),
+        )
+        .add_system(locale_text_system);
     }
 }
 
-pub struct History([u32; HISTORY_LEN]);
-
-lazy_static! {
-    static ref HISTORY_SCORE: Mutex<History> = Mutex::new(History([0; HISTORY_LEN]));
-}
-
-fn score_text_spawn_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn score_text_spawn_system(
+    mut commands: Commands,
+    fonts: Res<FontRegistry>,
+    locale: Res<Locale>,
+    leaderboard: Res<Leaderboard>,
+    game_state: Res<GameState>,
+    root_query: Query<Entity, With<PlayfieldRoot>>,
+) {
     // add score text resource
     commands
         .spawn(NodeBundle {
@@ -42,87 +63,100 @@ fn score_text_spawn_system(mut commands: Commands, asset_server: Res<AssetServer
             ..default()
         })
         .with_children(|builder| {
+            // 得分
+            builder
+                .spawn(TextBundle::from_sections([TextSection::new(
+                    get_score_text(&locale, 0, 1),
+                    fonts.style(&locale, FontStyle::HudLarge, Color::WHITE),
+                )]))
+                .insert(TextFonts(vec![FontStyle::HudLarge]))
+                .insert(ScoreText);
+
             // 当前分数
             builder
                 .spawn(TextBundle::from_sections([TextSection::new(
-                    get_current_score_text(0),
-                    TextStyle {
-                        font: asset_server.load("fonts/NotoSansSC-Light.otf"),
-                        font_size: COMMON_FONT_SIZE,
-                        color: Color::GREEN,
-                    },
+                    get_current_score_text(&locale, 0),
+                    fonts.style(&locale, FontStyle::Hud, Color::GREEN),
                 )]))
+                .insert(TextFonts(vec![FontStyle::Hud]))
                 .insert(CurrentScoreText);
 
             // 总分
             builder
                 .spawn(TextBundle::from_sections([TextSection::new(
-                    get_total_score_text(0),
-                    TextStyle {
-                        font: asset_server.load("fonts/NotoSansSC-Light.otf"),
-                        font_size: COMMON_FONT_SIZE,
-                        color: Color::ORANGE_RED,
-                    },
+                    get_total_score_text(&locale, 0),
+                    fonts.style(&locale, FontStyle::Hud, Color::ORANGE_RED),
                 )]))
+                .insert(TextFonts(vec![FontStyle::Hud]))
                 .insert(TotalScoreText);
 
             // 历史记录
             builder
                 .spawn(TextBundle::from_sections([TextSection::new(
-                    get_history_text(0),
-                    TextStyle {
-                        font: asset_server.load("fonts/NotoSansSC-Light.otf"),
-                        font_size: MIDDLE_FONT_SIZE,
-                        color: Color::YELLOW_GREEN,
-                    },
+                    get_history_text(&locale, &leaderboard, game_state.difficulty),
+                    fonts.style(&locale, FontStyle::HudLarge, Color::YELLOW_GREEN),
                 )]))
+                .insert(TextFonts(vec![FontStyle::HudLarge]))
                 .insert(HistoryScoreText);
-        });
+        })
+        .set_parent(root_query.single());
 }
 
-pub fn get_current_score_text(num: u32) -> String {
-    format!("当前歼灭: {}", num)
+/// Points of the run, with the combo multiplier while there is one
+pub fn get_score_text(locale: &Locale, points: u32, multiplier: u32) -> String {
+    let strings = locale.strings();
+    let mut txt = fill(strings.score, points);
+    if multiplier > 1 {
+        txt.push_str(format!("  {}", fill(strings.combo, multiplier)).as_str());
+    }
+    txt
 }
 
-pub fn get_total_score_text(num: u32) -> String {
-    format!("总共歼灭: {}", num)
+pub fn get_current_score_text(locale: &Locale, num: u32) -> String {
+    fill(locale.strings().current_score, num)
 }
 
-pub fn get_history_text(num: u32) -> String {
-    let mut history = HISTORY_SCORE.lock().unwrap();
-    let mut last_idx = history.0.len() - 1;
-    if num > history.0[last_idx] {
-        let mut idx = 0;
-        for (i, n) in history.0.iter_mut().enumerate() {
-            if *n < num {
-                idx = i;
-                while last_idx > i {
-                    history.0[last_idx] = history.0[last_idx - 1];
-                    last_idx -= 1;
-                }
-                break;
-            }
-        }
-        history.0[idx] = num;
-    }
-    let mut txt = "历史记录".to_string();
-    for (i, num) in history.0.iter().enumerate() {
-        txt.push_str(format!("\n#{}: {}", i + 1, *num).as_str());
+pub fn get_total_score_text(locale: &Locale, num: u32) -> String {
+    fill(locale.strings().total_score, num)
+}
+
+pub fn get_history_text(
+    locale: &Locale,
+    leaderboard: &Leaderboard,
+    difficulty: Difficulty,
+) -> String {
+    let strings = locale.strings();
+    let title = format!("{} ({})", strings.history, strings.difficulty(difficulty));
+    format_leaderboard(&title, leaderboard.entries(difficulty))
+}
+
+/// Title followed by one line per rank, empty ranks and unsigned entries show `---`
+pub fn format_leaderboard(title: &str, entries: &[LeaderboardEntry]) -> String {
+    let mut txt = title.to_string();
+    for i in 0..HISTORY_LEN {
+        let (name, score) = match entries.get(i) {
+            Some(entry) if !entry.name.is_empty() => (entry.name.as_str(), entry.score),
+            Some(entry) => ("---", entry.score),
+            None => ("---", 0),
+        };
+        txt.push_str(format!("\n#{} {}: {}", i + 1, name, score).as_str());
     }
     txt
 }
 
-fn lives_text_spawn_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn lives_text_spawn_system(
+    mut commands: Commands,
+    fonts: Res<FontRegistry>,
+    locale: Res<Locale>,
+    player_state: Res<PlayerState>,
+    root_query: Query<Entity, With<PlayfieldRoot>>,
+) {
     // add score text resource
     commands
         .spawn(
             TextBundle::from_sections([TextSection::new(
-                get_lives_text(consts::PLAYER_MAX_LIVES),
-                TextStyle {
-                    font: asset_server.load("fonts/NotoSansSC-Light.otf"),
-                    font_size: MIDDLE_FONT_SIZE,
-                    color: Color::GOLD,
-                },
+                get_lives_text(&locale, player_state.lives),
+                fonts.style(&locale, FontStyle::HudLarge, Color::GOLD),
             )])
             .with_text_alignment(TextAlignment::Center)
             .with_style(Style {
@@ -138,55 +172,293 @@ fn lives_text_spawn_system(mut commands: Commands, asset_server: Res<AssetServer
                 ..default()
             }),
         )
-        .insert(LifeText);
+        .insert(TextFonts(vec![FontStyle::HudLarge]))
+        .insert(LifeText)
+        .set_parent(root_query.single());
 }
 
-pub fn get_lives_text(num: u32) -> String {
-    format!("生命值: {}", num)
+pub fn get_lives_text(locale: &Locale, num: u32) -> String {
+    fill(locale.strings().lives, num)
+}
+
+/// One line per stat of the run
+pub fn format_run_summary(strings: &Strings, stats: &RunStats) -> String {
+    let kills_per_level = stats
+        .kills_per_level
+        .iter()
+        .map(|kills| kills.to_string())
+        .collect::<Vec<_>>()
+        .join(" / ");
+    let time = stats.time_survived as u32;
+    [
+        fill(
+            strings.stats_shots,
+            format!("{} / {}", stats.shots_fired, stats.hits),
+        ),
+        fill(strings.stats_accuracy, format!("{:.1}%", stats.accuracy())),
+        fill(strings.stats_kills_per_level, kills_per_level),
+        fill(strings.stats_deaths, stats.deaths),
+        fill(
+            strings.stats_time,
+            format!("{}:{:02}", time / 60, time % 60),
+        ),
+        fill(
+            strings.stats_fire_level,
+            stats.highest_fire_level as usize + 1,
+        ),
+        fill(strings.stats_combo, stats.longest_combo),
+    ]
+    .join("\n")
 }
 
 pub fn game_over_text_spawn(
     commands: &mut Commands,
-    asset_server: &Res<AssetServer>,
-    win_size: &Res<WinSize>,
+    fonts: &Res<FontRegistry>,
+    locale: &Res<Locale>,
+    run_stats: &RunStats,
+    root: Entity,
 ) {
-    let x = win_size.w / 2. - 48.;
-    let y = win_size.h / 2. - 100.;
     commands
         .spawn(NodeBundle {
             style: Style {
-                size: Size::new(Val::Px(100.), Val::Auto),
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
                 position_type: PositionType::Absolute,
-                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
-                flex_wrap: FlexWrap::Wrap,
-                position: UiRect {
-                    top: Val::Px(y),
-                    left: Val::Px(x),
-                    ..default()
-                },
                 ..default()
             },
-            background_color: BackgroundColor(Color::WHITE),
             ..default()
         })
         .with_children(|builder| {
-            builder.spawn(TextBundle::from_sections([TextSection::new(
-                "游戏结束",
-                TextStyle {
-                    font: asset_server.load("fonts/NotoSansSC-Medium.otf"),
-                    font_size: 32.,
-                    color: Color::RED,
-                },
-            )]));
-            builder.spawn(TextBundle::from_sections([TextSection::new(
-                "按[P]继续\n按[Esc]退出",
-                TextStyle {
-                    font: asset_server.load("fonts/NotoSansSC-Medium.otf"),
-                    font_size: 22.,
-                    color: Color::ORANGE_RED,
-                },
-            )]));
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(SIDE_MARGIN_PX)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::WHITE),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder
+                        .spawn(TextBundle::from_sections([TextSection::new(
+                            locale.strings().game_over,
+                            fonts.style(locale, FontStyle::Title, Color::RED),
+                        )]))
+                        .insert(TextFonts(vec![FontStyle::Title]))
+                        .insert(LocalizedText(|strings| strings.game_over));
+                    builder
+                        .spawn(TextBundle::from_sections([TextSection::new(
+                            format_run_summary(locale.strings(), run_stats),
+                            fonts.style(locale, FontStyle::Hud, Color::DARK_GRAY),
+                        )]))
+                        .insert(TextFonts(vec![FontStyle::Hud]))
+                        .insert(RunSummaryText);
+                    builder
+                        .spawn(
+                            TextBundle::from_sections([TextSection::new(
+                                locale.strings().game_over_prompt,
+                                fonts.style(locale, FontStyle::Prompt, Color::ORANGE_RED),
+                            )])
+                            .with_text_alignment(TextAlignment::Center),
+                        )
+                        .insert(TextFonts(vec![FontStyle::Prompt]))
+                        .insert(LocalizedText(|strings| strings.game_over_prompt));
+                });
         })
-        .insert(GameOverText);
+        .insert(GameOverText)
+        .set_parent(root);
+}
+
+fn set_text<T: Component>(mut query: Query<&mut Text, With<T>>, value: String) {
+    for mut text in &mut query {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+/// Scores and lives follow the player state, gameplay systems never touch the texts
+fn hud_text_system(
+    locale: Res<Locale>,
+    player_state: Res<PlayerState>,
+    combo: Res<Combo>,
+    mut text_set: ParamSet<(
+        Query<&mut Text, With<ScoreText>>,
+        Query<&mut Text, With<CurrentScoreText>>,
+        Query<&mut Text, With<TotalScoreText>>,
+        Query<&mut Text, With<LifeText>>,
+    )>,
+) {
+    if !player_state.is_changed() && !combo.is_changed() && !locale.is_changed() {
+        return;
+    }
+
+    set_text(
+        text_set.p0(),
+        get_score_text(&locale, player_state.points, combo.multiplier()),
+    );
+    set_text(
+        text_set.p1(),
+        get_current_score_text(&locale, player_state.current_score),
+    );
+    set_text(
+        text_set.p2(),
+        get_total_score_text(&locale, player_state.total_score),
+    );
+    set_text(text_set.p3(), get_lives_text(&locale, player_state.lives));
+}
+
+fn run_summary_text_system(
+    locale: Res<Locale>,
+    run_stats: Res<RunStats>,
+    query: Query<&mut Text, With<RunSummaryText>>,
+) {
+    if locale.is_changed() {
+        set_text(query, format_run_summary(locale.strings(), &run_stats));
+    }
+}
+
+fn history_text_system(
+    locale: Res<Locale>,
+    leaderboard: Res<Leaderboard>,
+    game_state: Res<GameState>,
+    query: Query<&mut Text, With<HistoryScoreText>>,
+) {
+    if leaderboard.is_changed() || game_state.is_changed() || locale.is_changed() {
+        set_text(
+            query,
+            get_history_text(&locale, &leaderboard, game_state.difficulty),
+        );
+    }
+}
+
+fn high_score_spawn_system(
+    mut commands: Commands,
+    fonts: Res<FontRegistry>,
+    locale: Res<Locale>,
+    mut new_high_score_events: EventReader<NewHighScore>,
+    root_query: Query<Entity, With<PlayfieldRoot>>,
+) {
+    for new_high_score in new_high_score_events.iter() {
+        commands
+            .spawn(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.), Val::Auto),
+                    position_type: PositionType::Absolute,
+                    justify_content: JustifyContent::Center,
+                    position: UiRect {
+                        top: Val::Percent(25.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|builder| {
+                builder
+                    .spawn(TextBundle::from_sections([TextSection::new(
+                        fill(locale.strings().new_high_score, new_high_score.rank),
+                        fonts.style(&locale, FontStyle::Title, Color::GOLD),
+                    )]))
+                    .insert(TextFonts(vec![FontStyle::Title]))
+                    .insert(HighScoreText {
+                        rank: new_high_score.rank,
+                    });
+            })
+            .insert(GameOverText)
+            .set_parent(root_query.single());
+    }
+}
+
+/// Flash the celebration between gold and orange
+fn high_score_text_system(
+    time: Res<Time>,
+    locale: Res<Locale>,
+    mut query: Query<(&mut Text, &HighScoreText)>,
+) {
+    let flash = (time.elapsed_seconds() * 6.).sin() > 0.;
+    for (mut text, high_score) in &mut query {
+        let value = fill(locale.strings().new_high_score, high_score.rank);
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+        text.sections[0].style.color = if flash { Color::GOLD } else { Color::ORANGE };
+    }
+}
+
+/// Re-render the static texts when the language changes, the HUD systems follow on their own
+fn locale_text_system(
+    locale: Res<Locale>,
+    fonts: Res<FontRegistry>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut text_set: ParamSet<(
+        Query<(&mut Text, &TextFonts)>,
+        Query<(&mut Text, &LocalizedText)>,
+    )>,
+) {
+    if !locale.is_changed() {
+        return;
+    }
+
+    if let Ok(mut window) = window_query.get_single_mut() {
+        window.title = locale.strings().window_title.to_string();
+    }
+
+    // two styles may share a font in one language and not in the other, so go by the style
+    for (mut text, text_fonts) in &mut text_set.p0() {
+        for (section, style) in text.sections.iter_mut().zip(&text_fonts.0) {
+            section.style.font = fonts.font(locale.language, *style);
+        }
+    }
+
+    for (mut text, localized) in &mut text_set.p1() {
+        text.sections[0].value = (localized.0)(locale.strings()).to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: u32, name: &str) -> LeaderboardEntry {
+        LeaderboardEntry {
+            score,
+            name: name.to_string(),
+            stats: RunStats::default(),
+            difficulty: Difficulty::Normal,
+        }
+    }
+
+    #[test]
+    fn format_empty_leaderboard() {
+        let expected = "Top".to_string()
+            + &(1..=HISTORY_LEN)
+                .map(|rank| format!("\n#{} ---: 0", rank))
+                .collect::<String>();
+        assert_eq!(format_leaderboard("Top", &[]), expected);
+    }
+
+    #[test]
+    fn format_full_leaderboard() {
+        let entries: Vec<LeaderboardEntry> = (0..HISTORY_LEN)
+            .map(|i| {
+                // the last one is left unsigned
+                let name = if i + 1 < HISTORY_LEN { "ABC" } else { "" };
+                entry(100 - i as u32 * 10, name)
+            })
+            .collect();
+
+        let txt = format_leaderboard("Top", &entries);
+        let lines: Vec<&str> = txt.lines().collect();
+        assert_eq!(lines.len(), HISTORY_LEN + 1);
+        assert_eq!(lines[0], "Top");
+        assert_eq!(lines[1], "#1 ABC: 100");
+        assert_eq!(
+            lines[HISTORY_LEN],
+            format!("#{} ---: {}", HISTORY_LEN, 110 - HISTORY_LEN as u32 * 10)
+        );
+    }
 }
@@ -1,4 +1,68 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::{
+    path::Path,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::AssetServer;
+use rand::{rngs::StdRng, Error, RngCore, SeedableRng};
+
+/// One stream of randomness per consumer, each drawn by a single system,
+/// so systems running in parallel can't reorder each other's draws
+#[derive(Clone, Copy)]
+pub enum RngStream {
+    PlayerFire,
+    EnemyFire,
+    // the formation then the enemy kind, in that order by `spawn_enemy`
+    Spawn,
+}
+
+impl RngStream {
+    const COUNT: usize = 3;
+}
+
+static RNGS: [Mutex<Option<StdRng>>; RngStream::COUNT] =
+    [Mutex::new(None), Mutex::new(None), Mutex::new(None)];
+
+/// Randomness of the game, seeded from entropy until [`seed`] fixes it
+pub struct GameRng(RngStream);
+
+pub fn rng(stream: RngStream) -> GameRng {
+    GameRng(stream)
+}
+
+/// Replay the same draws in every stream, `seed` in the dev console.
+/// When they are drawn still follows the frame times
+pub fn seed(seed: u64) {
+    for (i, rng) in RNGS.iter().enumerate() {
+        *rng.lock().unwrap() = Some(StdRng::seed_from_u64(seed.wrapping_add(i as u64)));
+    }
+}
+
+impl GameRng {
+    fn with<T>(&self, f: impl FnOnce(&mut StdRng) -> T) -> T {
+        let mut rng = RNGS[self.0 as usize].lock().unwrap();
+        f(rng.get_or_insert_with(StdRng::from_entropy))
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.with(|rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.with(|rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.with(|rng| rng.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.with(|rng| rng.try_fill_bytes(dest))
+    }
+}
 
 #[allow(dead_code)]
 pub fn now_unix_sec() -> u64 {
@@ -7,3 +71,13 @@ pub fn now_unix_sec() -> u64 {
         .unwrap()
         .as_secs()
 }
+
+/// Whether the asset source (embedded or the assets folder) has a file at `path`
+pub fn asset_exists(asset_server: &AssetServer, path: &str) -> bool {
+    let path = Path::new(path);
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    match asset_server.asset_io().read_directory(dir) {
+        Ok(mut files) => files.any(|file| file == path),
+        Err(_) => false,
+    }
+}